@@ -0,0 +1,90 @@
+//! Compares drawing sprites one call at a time against drawing them through
+//! a `Batch`, using SDL's software renderer so that no window is needed.
+//! Sprites overlap and switch textures and alpha the way a busy scene does,
+//! mostly staying on the same texture for a few draws in a row.
+//!
+//! Run with `cargo bench --bench batch`.
+
+use moho::renderer::{align, options, Batch, ColorRGBA, Renderer};
+
+use sdl2::{pixels::PixelFormatEnum, render::Canvas, surface::Surface};
+
+use std::time::{Duration, Instant};
+
+const SPRITES: usize = 10_000;
+const FRAMES: u32 = 20;
+
+fn time(mut frame: impl FnMut()) -> Duration {
+    frame();
+    let start = Instant::now();
+    (0..FRAMES).for_each(|_| frame());
+    start.elapsed() / FRAMES
+}
+
+/// Small xorshift generator so that every run draws the same scene
+struct Random(u32);
+
+impl Random {
+    fn next(&mut self, below: u32) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 % below
+    }
+}
+
+fn main() {
+    let surface = Surface::new(640, 480, PixelFormatEnum::RGBA8888).unwrap();
+    let mut canvas = Canvas::from_surface(surface).unwrap();
+    let creator = canvas.texture_creator();
+    let textures: Vec<_> = (0..4)
+        .map(|_| {
+            let sprite = Surface::new(16, 16, PixelFormatEnum::RGBA8888).unwrap();
+            creator.create_texture_from_surface(&sprite).unwrap()
+        })
+        .collect();
+
+    let mut random = Random(0x2545_f491);
+    let mut texture = 0;
+    let sprites: Vec<_> = (0..SPRITES)
+        .map(|_| {
+            if random.next(4) == 0 {
+                texture = random.next(textures.len() as u32) as usize;
+            }
+            let dst = align::left(random.next(624) as i32)
+                .top(random.next(464) as i32)
+                .dims(glm::uvec2(16, 16));
+            let alpha = [255, 192, 128][random.next(3) as usize];
+            let options = options::at(dst).color(ColorRGBA(255, 255, 255, alpha));
+            (&textures[texture], options)
+        })
+        .collect();
+
+    let direct = time(|| {
+        sprites
+            .iter()
+            .try_for_each(|(texture, options)| canvas.draw(*texture, options.clone()))
+            .unwrap()
+    });
+
+    let mut batch = Batch::new();
+    let batched = time(|| {
+        sprites
+            .iter()
+            .for_each(|(texture, options)| batch.draw(*texture, options.clone()));
+        batch.flush(&mut canvas).unwrap()
+    });
+
+    let runs = 1 + sprites
+        .windows(2)
+        .filter(|pair| !std::ptr::eq(pair[0].0, pair[1].0))
+        .count();
+    println!(
+        "{} sprites over {} textures in {} runs",
+        SPRITES,
+        textures.len(),
+        runs
+    );
+    println!("per call: {:?} per frame", direct);
+    println!("batched:  {:?} per frame", batched);
+}
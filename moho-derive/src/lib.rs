@@ -5,7 +5,7 @@ extern crate synstructure;
 
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::parse_quote;
+use syn::{parse_quote, Attribute, Lit, Meta, NestedMeta};
 use synstructure::{decl_derive, AddBounds, Structure};
 
 decl_derive!([Show, attributes(moho)] => show_derive);
@@ -13,10 +13,13 @@ decl_derive!([Show, attributes(moho)] => show_derive);
 fn show_derive(mut structure: Structure) -> TokenStream {
     let moho_skip = parse_quote!(#[moho(skip)]);
     structure.filter(|bi| !bi.ast().attrs.iter().any(|a| *a == moho_skip));
-    let body = structure.each(|bi| {
-        quote! {
+    let body = structure.each(|bi| match layer(&bi.ast().attrs) {
+        Some(layer) => quote! {
+            renderer.at_depth(#layer, |renderer| renderer.show(#bi))?;
+        },
+        None => quote! {
             renderer.show(#bi)?;
-        }
+        },
     });
 
     structure.add_bounds(AddBounds::Fields).gen_impl(quote! {
@@ -28,3 +31,29 @@ fn show_derive(mut structure: Structure) -> TokenStream {
         }
     })
 }
+
+/// `#[moho(layer = N)]` shows a field at layer `N` of renderers that sort by
+/// depth
+fn layer(attrs: &[Attribute]) -> Option<i32> {
+    let nested = attrs
+        .iter()
+        .filter_map(|a| a.parse_meta().ok())
+        .filter_map(|meta| match meta {
+            Meta::List(ref list) if list.ident == "moho" => Some(list.nested.clone()),
+            _ => None,
+        })
+        .flatten();
+    for meta in nested {
+        if let NestedMeta::Meta(Meta::NameValue(ref value)) = meta {
+            if value.ident == "layer" {
+                match value.lit {
+                    Lit::Int(ref layer) if layer.value() <= i32::MAX as u64 => {
+                        return Some(layer.value() as i32);
+                    }
+                    _ => panic!("#[moho(layer = ..)] expects a layer number"),
+                }
+            }
+        }
+    }
+    None
+}
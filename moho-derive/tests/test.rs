@@ -2,7 +2,7 @@ extern crate glm;
 extern crate moho;
 
 use moho::{
-    renderer::{align, ColorRGBA, Destination, Draw, Options, Renderer, Show},
+    renderer::{align, ColorRGBA, Depth, Destination, Draw, Options, Renderer, Show},
     texture::Texture,
 };
 
@@ -28,6 +28,12 @@ mod inner {
         pub ignored: i32,
     }
 
+    #[derive(moho::Show)]
+    pub struct Layered<T> {
+        #[moho(layer = 2)]
+        pub front: T,
+        pub back: T,
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -44,6 +50,7 @@ impl Texture for MockTexture {
 impl Show<MockRenderer> for MockTexture {
     fn show(&self, renderer: &mut MockRenderer) -> moho::Result<()> {
         renderer.shown.push(*self);
+        renderer.depths.push(renderer.depth);
         Ok(())
     }
 }
@@ -59,6 +66,8 @@ impl Draw<MockRenderer> for MockTexture {
 struct MockRenderer {
     drawn: Vec<(MockTexture, Options)>,
     shown: Vec<MockTexture>,
+    depths: Vec<Depth>,
+    depth: Depth,
 }
 
 impl Renderer for MockRenderer {
@@ -71,6 +80,10 @@ impl Renderer for MockRenderer {
     fn draw_rects(&mut self, _: &[Destination]) -> moho::Result<()> {
         Ok(())
     }
+
+    fn set_depth(&mut self, depth: Depth) -> Depth {
+        std::mem::replace(&mut self.depth, depth)
+    }
 }
 
 #[test]
@@ -98,3 +111,23 @@ fn test() {
         vec![(assets.image.texture, options::at(assets.image.dst))]
     );
 }
+
+#[test]
+fn layers() {
+    use crate::inner::*;
+
+    let layered = Layered {
+        front: MockTexture {
+            dims: glm::uvec2(1, 1),
+        },
+        back: MockTexture {
+            dims: glm::uvec2(2, 2),
+        },
+    };
+
+    let mut renderer = MockRenderer::default();
+    assert!(renderer.show(&layered).is_ok());
+    assert_eq!(renderer.shown, vec![layered.front, layered.back]);
+    assert_eq!(renderer.depths, vec![Depth::layer(2), Depth::layer(0)]);
+    assert_eq!(renderer.depth, Depth::default());
+}
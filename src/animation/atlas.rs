@@ -0,0 +1,114 @@
+use super::{tile_sheet::Frame, Tile};
+
+use std::collections::HashMap;
+
+/// Named, arbitrarily placed regions of a single texture
+#[derive(Debug, Clone)]
+pub struct Atlas<T> {
+    texture: T,
+    regions: HashMap<String, Frame>,
+}
+
+impl<T> Atlas<T> {
+    pub fn new(texture: T) -> Self {
+        Atlas {
+            texture,
+            regions: HashMap::new(),
+        }
+    }
+
+    pub fn from_frames(frames: HashMap<String, Frame>, texture: T) -> Self {
+        Atlas {
+            texture,
+            regions: frames,
+        }
+    }
+
+    pub fn region(mut self, name: impl Into<String>, src: glm::UVec4) -> Self {
+        self.regions.insert(name.into(), Frame { src, trim: None });
+        self
+    }
+
+    pub fn tile(&self, name: &str) -> Option<Tile<'_, T>> {
+        self.regions
+            .get(name)
+            .map(|&frame| Tile::new(&self.texture, frame))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.regions.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animation::{import::Sheet, Trim},
+        renderer::{mocks::MockCanvas, options, Renderer},
+        texture::mocks::MockTexture,
+    };
+
+    #[test]
+    fn tile_by_name() {
+        let texture = MockTexture {
+            dims: glm::uvec2(64, 32),
+        };
+        let atlas = Atlas::new(texture)
+            .region("button", glm::uvec4(0, 0, 48, 16))
+            .region("cursor", glm::uvec4(48, 0, 8, 8));
+
+        let tile = atlas.tile("cursor").expect("missing cursor region");
+        assert_eq!(tile.rect(), glm::uvec4(48, 0, 8, 8));
+        assert!(atlas.tile("arrow").is_none());
+
+        let mut names: Vec<_> = atlas.names().collect();
+        names.sort();
+        assert_eq!(names, vec!["button", "cursor"]);
+    }
+
+    #[test]
+    fn draws_regions() {
+        let texture = MockTexture {
+            dims: glm::uvec2(64, 32),
+        };
+        let atlas = Atlas::new(texture).region("button", glm::uvec4(0, 16, 48, 16));
+
+        let mut renderer = MockCanvas::new();
+        let tile = atlas.tile("button").unwrap();
+        assert!(renderer.show(&tile).is_ok());
+        assert_eq!(
+            renderer.draw,
+            vec![(texture, options::from(glm::uvec4(0, 16, 48, 16)))]
+        );
+    }
+
+    #[test]
+    fn from_imported_sheet() {
+        let json = r#"{
+            "frames": [
+                {
+                    "filename": "star.png",
+                    "frame": { "x": 4, "y": 0, "w": 6, "h": 6 },
+                    "trimmed": true,
+                    "spriteSourceSize": { "x": 1, "y": 1, "w": 6, "h": 6 },
+                    "sourceSize": { "w": 8, "h": 8 }
+                }
+            ],
+            "meta": { "image": "ui.png" }
+        }"#;
+        let texture = MockTexture {
+            dims: glm::uvec2(16, 8),
+        };
+        let atlas = Sheet::parse(json).unwrap().atlas(texture);
+        let tile = atlas.tile("star.png").unwrap();
+        assert_eq!(tile.rect(), glm::uvec4(4, 0, 6, 6));
+        assert_eq!(tile.dims(), glm::uvec2(8, 8));
+
+        let trim = Trim {
+            offset: glm::uvec2(1, 1),
+            source: glm::uvec2(8, 8),
+        };
+        assert_eq!(atlas.regions["star.png"].trim, Some(trim));
+    }
+}
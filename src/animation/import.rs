@@ -0,0 +1,494 @@
+use super::{
+    animator,
+    tile_sheet::{Frame, Trim},
+    Atlas, Data, TileSheet,
+};
+use crate::Result;
+
+use serde::Deserialize;
+
+use std::{collections::HashMap, fs, iter, path::Path, time::Duration};
+
+/// Aseprite does not write a duration when exporting through TexturePacker
+/// compatible formats; this matches the default it uses for new frames
+const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+/// Shortest step that frames are repeated by to match their durations;
+/// durations without a coarser common step get rounded to it instead
+const MIN_STEP: Duration = Duration::from_millis(10);
+
+/// Frame data exported by Aseprite or TexturePacker as JSON (hash or array)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet {
+    pub image: String,
+    pub frames: Vec<SheetFrame>,
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetFrame {
+    pub name: String,
+    pub frame: Frame,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    pub from: u32,
+    pub to: u32,
+    pub direction: Direction,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Direction {
+    #[default]
+    #[serde(rename = "forward")]
+    Forward,
+    #[serde(rename = "reverse")]
+    Reverse,
+    #[serde(rename = "pingpong")]
+    PingPong,
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+impl Sheet {
+    pub fn parse(json: &str) -> Result<Sheet> {
+        let raw: RawSheet = serde_json::from_str(json)?;
+        let frames = match raw.frames {
+            RawFrames::Array(frames) => frames
+                .into_iter()
+                .map(|f| f.frame.into_frame(f.filename))
+                .collect::<Result<Vec<_>>>()?,
+            RawFrames::Hash(frames) => frames
+                .into_iter()
+                .map(|(name, f)| serde_json::from_value::<RawFrame>(f)?.into_frame(name))
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        let tags = raw
+            .meta
+            .frame_tags
+            .into_iter()
+            .map(|t| {
+                let tag = Tag {
+                    name: t.name,
+                    from: t.from,
+                    to: t.to,
+                    direction: t.direction,
+                };
+                tag.check(frames.len())?;
+                Ok(tag)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Sheet {
+            image: raw.meta.image,
+            frames,
+            tags,
+        })
+    }
+
+    /// Reads the sheet from disk, resolving the image relative to it
+    pub fn load(path: impl AsRef<Path>) -> Result<Sheet> {
+        let path = path.as_ref();
+        let mut sheet = Sheet::parse(&fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            sheet.image = dir.join(&sheet.image).to_string_lossy().into_owned();
+        }
+        Ok(sheet)
+    }
+
+    pub fn tile_sheet<T>(&self, texture: T) -> Result<TileSheet<T>> {
+        let frames = self.frames.iter().map(|f| f.frame).collect();
+        TileSheet::from_frames(frames, texture)
+    }
+
+    pub fn atlas<T>(&self, texture: T) -> Atlas<T> {
+        let frames = self
+            .frames
+            .iter()
+            .map(|f| (f.name.clone(), f.frame))
+            .collect();
+        Atlas::from_frames(frames, texture)
+    }
+
+    /// Frames with longer durations are repeated so that the whole tag can
+    /// be played back by an animator with a single frame duration
+    pub fn animation<T>(&self, tag: &Tag, texture: T) -> Result<Data<T>> {
+        tag.check(self.frames.len())?;
+        let (from, to) = (tag.from as usize, tag.to as usize);
+        let indices: Vec<usize> = match tag.direction {
+            Direction::Forward => (from..=to).collect(),
+            Direction::Reverse => (from..=to).rev().collect(),
+            Direction::PingPong => (from..=to).chain((from + 1..to).rev()).collect(),
+            Direction::PingPongReverse => (from..=to).rev().chain(from + 1..to).collect(),
+        };
+
+        let step = indices
+            .iter()
+            .map(|&i| self.frames[i].duration.as_nanos())
+            .fold(0, gcd)
+            .max(MIN_STEP.as_nanos());
+        let repeats = |duration: Duration| {
+            let repeats = (duration.as_nanos() + step / 2) / step;
+            repeats.max(1) as usize
+        };
+
+        let frames: Vec<_> = indices
+            .iter()
+            .map(|&i| &self.frames[i])
+            .flat_map(|f| iter::repeat(f.frame).take(repeats(f.duration)))
+            .collect();
+
+        Ok(Data {
+            animator: animator::Data {
+                max: frames.len() as u32,
+                duration: Duration::from_nanos(step as u64),
+            },
+            sheet: TileSheet::from_frames(frames, texture)?,
+        })
+    }
+
+    pub fn animations<T: Clone>(&self, texture: T) -> Result<HashMap<String, Data<T>>> {
+        self.tags
+            .iter()
+            .map(|t| Ok((t.name.clone(), self.animation(t, texture.clone())?)))
+            .collect()
+    }
+}
+
+impl Tag {
+    fn check(&self, frames: usize) -> Result<()> {
+        if self.from > self.to || self.to as usize >= frames {
+            return Err(failure::err_msg(format!(
+                "tag '{}' spans frames {}..={} but the sheet has {} frames",
+                self.name, self.from, self.to, frames
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[derive(Deserialize)]
+struct RawSheet {
+    frames: RawFrames,
+    meta: RawMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawFrames {
+    Array(Vec<RawNamedFrame>),
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct RawNamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: RawFrame,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawFrame {
+    frame: RawRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: Option<RawRect>,
+    source_size: Option<RawSize>,
+    duration: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RawRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct RawSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMeta {
+    image: String,
+    #[serde(default)]
+    frame_tags: Vec<RawTag>,
+}
+
+#[derive(Deserialize)]
+struct RawTag {
+    name: String,
+    from: u32,
+    to: u32,
+    #[serde(default)]
+    direction: Direction,
+}
+
+impl RawFrame {
+    fn into_frame(self, name: String) -> Result<SheetFrame> {
+        if self.rotated {
+            return Err(failure::err_msg(format!(
+                "frame '{}' is rotated; rotated frames are not supported",
+                name
+            )));
+        }
+
+        let duration = match self.duration {
+            Some(0) => {
+                return Err(failure::err_msg(format!(
+                    "frame '{}' has a duration of zero",
+                    name
+                )))
+            }
+            Some(ms) => Duration::from_millis(ms),
+            None => DEFAULT_FRAME_DURATION,
+        };
+
+        let trim = match (self.trimmed, self.sprite_source_size, self.source_size) {
+            (true, _, Some(RawSize { w: 0, .. })) | (true, _, Some(RawSize { h: 0, .. })) => {
+                return Err(failure::err_msg(format!(
+                    "frame '{}' is trimmed from an empty source",
+                    name
+                )))
+            }
+            (true, Some(placed), Some(source)) => Some(Trim {
+                offset: glm::uvec2(placed.x, placed.y),
+                source: glm::uvec2(source.w, source.h),
+            }),
+            _ => None,
+        };
+
+        let RawRect { x, y, w, h } = self.frame;
+        Ok(SheetFrame {
+            name,
+            frame: Frame {
+                src: glm::uvec4(x, y, w, h),
+                trim,
+            },
+            duration,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture::mocks::MockTexture;
+
+    const ASEPRITE: &str = r#"{
+        "frames": {
+            "hero 0.aseprite": {
+                "frame": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "sourceSize": { "w": 16, "h": 16 },
+                "duration": 100
+            },
+            "hero 1.aseprite": {
+                "frame": { "x": 16, "y": 0, "w": 12, "h": 14 },
+                "rotated": false,
+                "trimmed": true,
+                "spriteSourceSize": { "x": 2, "y": 1, "w": 12, "h": 14 },
+                "sourceSize": { "w": 16, "h": 16 },
+                "duration": 200
+            },
+            "hero 2.aseprite": {
+                "frame": { "x": 28, "y": 0, "w": 16, "h": 16 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+                "sourceSize": { "w": 16, "h": 16 },
+                "duration": 100
+            }
+        },
+        "meta": {
+            "app": "http://www.aseprite.org/",
+            "image": "hero.png",
+            "frameTags": [
+                { "name": "idle", "from": 0, "to": 0, "direction": "forward" },
+                { "name": "walk", "from": 0, "to": 2, "direction": "pingpong" }
+            ]
+        }
+    }"#;
+
+    const TEXTURE_PACKER: &str = r#"{
+        "frames": [
+            {
+                "filename": "coin.png",
+                "frame": { "x": 2, "y": 2, "w": 8, "h": 8 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 8, "h": 8 },
+                "sourceSize": { "w": 8, "h": 8 }
+            },
+            {
+                "filename": "gem.png",
+                "frame": { "x": 12, "y": 2, "w": 6, "h": 10 },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": 6, "h": 10 },
+                "sourceSize": { "w": 6, "h": 10 }
+            }
+        ],
+        "meta": { "image": "items.png", "size": { "w": 32, "h": 16 } }
+    }"#;
+
+    #[test]
+    fn parses_aseprite_hash() {
+        let sheet = Sheet::parse(ASEPRITE).unwrap();
+        assert_eq!(sheet.image, "hero.png");
+        assert_eq!(sheet.frames.len(), 3);
+        assert_eq!(sheet.frames[1].name, "hero 1.aseprite");
+        assert_eq!(sheet.frames[1].duration, Duration::from_millis(200));
+        assert_eq!(
+            sheet.frames[1].frame,
+            Frame {
+                src: glm::uvec4(16, 0, 12, 14),
+                trim: Some(Trim {
+                    offset: glm::uvec2(2, 1),
+                    source: glm::uvec2(16, 16),
+                }),
+            }
+        );
+        assert_eq!(sheet.frames[0].frame.trim, None);
+        assert_eq!(
+            sheet.tags[1],
+            Tag {
+                name: "walk".into(),
+                from: 0,
+                to: 2,
+                direction: Direction::PingPong,
+            }
+        );
+    }
+
+    #[test]
+    fn parses_texture_packer_array() {
+        let sheet = Sheet::parse(TEXTURE_PACKER).unwrap();
+        assert_eq!(sheet.image, "items.png");
+        assert!(sheet.tags.is_empty());
+        let names: Vec<_> = sheet.frames.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["coin.png", "gem.png"]);
+        assert_eq!(sheet.frames[1].frame.src, glm::uvec4(12, 2, 6, 10));
+        assert_eq!(sheet.frames[1].duration, DEFAULT_FRAME_DURATION);
+
+        let texture = MockTexture {
+            dims: glm::uvec2(32, 16),
+        };
+        let tiles = sheet.tile_sheet(texture).unwrap();
+        assert_eq!(tiles.tile(1).rect(), glm::uvec4(12, 2, 6, 10));
+    }
+
+    #[test]
+    fn rejects_rotated_frames() {
+        let json = TEXTURE_PACKER.replacen(r#""rotated": false"#, r#""rotated": true"#, 1);
+        assert!(Sheet::parse(&json).is_err());
+    }
+
+    #[test]
+    fn rejects_out_of_range_tags() {
+        let json = ASEPRITE.replace(r#""to": 2"#, r#""to": 3"#);
+        assert!(Sheet::parse(&json).is_err());
+    }
+
+    #[test]
+    fn animations_per_tag() {
+        let texture = MockTexture {
+            dims: glm::uvec2(44, 16),
+        };
+        let sheet = Sheet::parse(ASEPRITE).unwrap();
+        let animations = sheet.animations(texture).unwrap();
+        assert_eq!(animations.len(), 2);
+
+        let idle = &animations["idle"];
+        assert_eq!(idle.animator.max, 1);
+        assert_eq!(idle.animator.duration, Duration::from_millis(100));
+
+        // pingpong 0, 1, 2, 1 with frame 1 lasting twice as long
+        let walk = &animations["walk"];
+        assert_eq!(walk.animator.max, 6);
+        assert_eq!(walk.animator.duration, Duration::from_millis(100));
+        let rects: Vec<_> = (0..6).map(|i| walk.sheet.tile(i).rect().x).collect();
+        assert_eq!(rects, vec![0, 16, 16, 28, 16, 16]);
+    }
+
+    #[test]
+    fn reverse_animation() {
+        let texture = MockTexture {
+            dims: glm::uvec2(44, 16),
+        };
+        let sheet = Sheet::parse(ASEPRITE).unwrap();
+        let tag = Tag {
+            name: "back".into(),
+            from: 1,
+            to: 2,
+            direction: Direction::Reverse,
+        };
+        let animation = sheet.animation(&tag, texture).unwrap();
+        assert_eq!(animation.animator.max, 3);
+        let rects: Vec<_> = (0..3).map(|i| animation.sheet.tile(i).rect().x).collect();
+        assert_eq!(rects, vec![28, 16, 16]);
+    }
+
+    #[test]
+    fn rejects_edited_tags() {
+        let texture = MockTexture {
+            dims: glm::uvec2(44, 16),
+        };
+        let sheet = Sheet::parse(ASEPRITE).unwrap();
+        let tag = Tag {
+            name: "past the end".into(),
+            from: 2,
+            to: 5,
+            direction: Direction::Forward,
+        };
+        assert!(sheet.animation(&tag, texture).is_err());
+    }
+
+    #[test]
+    fn rounds_coprime_durations() {
+        let texture = MockTexture {
+            dims: glm::uvec2(44, 16),
+        };
+        let json = ASEPRITE
+            .replace(r#""duration": 200"#, r#""duration": 33"#)
+            .replace(r#""duration": 100"#, r#""duration": 101"#);
+        let sheet = Sheet::parse(&json).unwrap();
+        let walk = sheet.animation(&sheet.tags[1], texture).unwrap();
+        assert_eq!(walk.animator.duration, MIN_STEP);
+        // 0, 1, 2, 1 as 10, 3, 10 and 3 steps
+        assert_eq!(walk.animator.max, 26);
+    }
+
+    #[test]
+    fn rejects_empty_trim_sources() {
+        let json = ASEPRITE.replacen(
+            r#""sourceSize": { "w": 16, "h": 16 },
+                "duration": 200"#,
+            r#""sourceSize": { "w": 0, "h": 16 },
+                "duration": 200"#,
+            1,
+        );
+        assert!(Sheet::parse(&json).is_err());
+    }
+}
@@ -1,12 +1,15 @@
+mod atlas;
 mod data;
 mod tile_sheet;
 
 pub mod animator;
+pub mod import;
 
 pub use self::{
     animator::Animator,
+    atlas::Atlas,
     data::Data,
-    tile_sheet::{Tile, TileSheet},
+    tile_sheet::{Frame, Grid, Tile, TileSheet, Trim},
 };
 
 use std::time::Duration;
@@ -1,5 +1,5 @@
 use crate::{
-    renderer::{options, Draw, Options, Renderer, Show},
+    renderer::{align, options, Destination, Draw, FDestination, Options, Renderer, Show},
     texture::Texture,
     Result,
 };
@@ -7,14 +7,74 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct TileSheet<T> {
     texture: T,
-    tiles: glm::UVec2,
-    dimensions: glm::UVec2,
+    layout: Layout,
+}
+
+#[derive(Debug, Clone)]
+enum Layout {
+    Grid { columns: u32, grid: Grid },
+    Frames(Vec<Frame>),
+}
+
+/// Uniformly sized tiles with an optional margin around the whole texture
+/// and spacing between each tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    pub tile: glm::UVec2,
+    pub margin: u32,
+    pub spacing: u32,
+}
+
+impl Grid {
+    pub fn new(tile: glm::UVec2) -> Self {
+        Grid {
+            tile,
+            margin: 0,
+            spacing: 0,
+        }
+    }
+
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    fn columns(&self, dims: glm::UVec2) -> u32 {
+        (dims.x + self.spacing).saturating_sub(2 * self.margin) / (self.tile.x + self.spacing)
+    }
+
+    fn src(&self, columns: u32, index: u32) -> glm::UVec4 {
+        let tile_pos = glm::uvec2(index % columns, index / columns);
+        let stride = self.tile + glm::uvec2(self.spacing, self.spacing);
+        let position = glm::uvec2(self.margin, self.margin) + tile_pos * stride;
+        glm::uvec4(position.x, position.y, self.tile.x, self.tile.y)
+    }
+}
+
+/// A single frame of a sheet that is not laid out in a uniform grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub src: glm::UVec4,
+    pub trim: Option<Trim>,
+}
+
+/// Where a trimmed frame sits inside its original, untrimmed sprite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trim {
+    pub offset: glm::UVec2,
+    pub source: glm::UVec2,
 }
 
 #[derive(Debug)]
 pub struct Tile<'a, T> {
     texture: &'a T,
     src: glm::UVec4,
+    trim: Option<Trim>,
 }
 
 // https://github.com/rust-lang/rust/issues/40754
@@ -31,23 +91,73 @@ impl<T> Clone for Tile<'_, T> {
 
 impl<T: Texture> TileSheet<T> {
     pub fn new(tiles: glm::UVec2, texture: T) -> Self {
+        let grid = Grid::new(texture.dims() / tiles);
         TileSheet {
-            dimensions: texture.dims() / tiles,
+            layout: Layout::Grid {
+                columns: tiles.x,
+                grid,
+            },
             texture,
-            tiles,
         }
     }
+
+    /// Fails if the tiles are empty or not a single column of them fits
+    pub fn from_grid(grid: Grid, texture: T) -> Result<Self> {
+        if grid.tile.x == 0 || grid.tile.y == 0 {
+            return Err(failure::err_msg(format!(
+                "tiles must not be empty but are {}x{}",
+                grid.tile.x, grid.tile.y
+            )));
+        }
+        let columns = grid.columns(texture.dims());
+        if columns == 0 {
+            return Err(failure::err_msg(format!(
+                "no {}x{} tiles fit in a texture {} wide",
+                grid.tile.x,
+                grid.tile.y,
+                texture.dims().x
+            )));
+        }
+        Ok(TileSheet {
+            layout: Layout::Grid { columns, grid },
+            texture,
+        })
+    }
 }
 
 impl<T> TileSheet<T> {
+    pub fn from_frames(frames: Vec<Frame>, texture: T) -> Result<Self> {
+        if frames.is_empty() {
+            return Err(failure::err_msg("a tile sheet needs at least one frame"));
+        }
+        Ok(TileSheet {
+            texture,
+            layout: Layout::Frames(frames),
+        })
+    }
+
+    /// Sheets made from frames wrap indices past the last frame around to
+    /// the first one; grid sheets do not know how many rows are in use, so
+    /// such indices land past the bottom of the texture
     pub fn tile(&self, index: u32) -> Tile<'_, T> {
-        let tile_pos = glm::uvec2(index % self.tiles.x, index / self.tiles.x);
-        let position = tile_pos * self.dimensions;
-        let src = glm::uvec4(position.x, position.y, self.dimensions.x, self.dimensions.y);
+        let frame = match self.layout {
+            Layout::Grid { columns, grid } => Frame {
+                src: grid.src(columns, index),
+                trim: None,
+            },
+            Layout::Frames(ref frames) => frames[index as usize % frames.len()],
+        };
+
+        Tile::new(&self.texture, frame)
+    }
+}
 
+impl<'a, T> Tile<'a, T> {
+    pub(super) fn new(texture: &'a T, frame: Frame) -> Self {
         Tile {
-            texture: &self.texture,
-            src,
+            texture,
+            src: frame.src,
+            trim: frame.trim,
         }
     }
 }
@@ -56,6 +166,52 @@ impl<T> Tile<'_, T> {
     pub fn rect(&self) -> glm::UVec4 {
         self.src
     }
+
+    /// Dimensions of the sprite before any trimming was applied
+    pub fn dims(&self) -> glm::UVec2 {
+        match self.trim {
+            Some(trim) => trim.source,
+            None => glm::uvec2(self.src.z, self.src.w),
+        }
+    }
+
+    fn options(&self, mut options: Options) -> Options {
+        let trimmed = glm::uvec2(self.src.z, self.src.w);
+        // an empty source has no area to place the trimmed frame in
+        let trim = self.trim.filter(|t| t.source.x > 0 && t.source.y > 0);
+        match (trim, options.precise_dst(), options.dst) {
+            (Some(trim), Some(fdst), _) => {
+                options = options.at_precise(trim.apply_precise(fdst, trimmed))
+            }
+            (Some(trim), None, Some(dst)) => options.dst = Some(trim.apply(dst, trimmed)),
+            _ => {}
+        }
+        options.from(self.src)
+    }
+}
+
+impl Trim {
+    /// Shrinks a destination meant for the untrimmed sprite down to the
+    /// area covered by the trimmed frame
+    fn apply(self, dst: Destination, trimmed: glm::UVec2) -> Destination {
+        let offset = self.offset * dst.dims / self.source;
+        let dims = trimmed * dst.dims / self.source;
+        align::left(dst.left() + offset.x as i32)
+            .top(dst.top() + offset.y as i32)
+            .dims(dims)
+    }
+
+    fn apply_precise(self, dst: FDestination, trimmed: glm::UVec2) -> FDestination {
+        let scale = dst.dims / glm::to_dvec2(self.source);
+        let offset = glm::to_dvec2(self.offset) * scale;
+        FDestination::new(dst.top_left + offset, glm::to_dvec2(trimmed) * scale)
+    }
+}
+
+impl<T> Texture for Tile<'_, T> {
+    fn dims(&self) -> glm::UVec2 {
+        Tile::dims(self)
+    }
 }
 
 impl<R: Renderer, T: Draw<R>> Show<R> for Tile<'_, T> {
@@ -66,7 +222,7 @@ impl<R: Renderer, T: Draw<R>> Show<R> for Tile<'_, T> {
 
 impl<R: Renderer, T: Draw<R>> Draw<R> for Tile<'_, T> {
     fn draw(&self, options: Options, renderer: &mut R) -> Result<()> {
-        renderer.draw(self.texture, options.from(self.src))
+        renderer.draw(self.texture, self.options(options))
     }
 }
 
@@ -74,7 +230,7 @@ impl<R: Renderer, T: Draw<R>> Draw<R> for Tile<'_, T> {
 mod tests {
     use super::*;
     use crate::{
-        renderer::{mocks::MockCanvas, options::Flip},
+        renderer::{align, mocks::MockCanvas, options::Flip},
         texture::mocks::MockTexture,
     };
 
@@ -132,6 +288,7 @@ mod tests {
         let tile = Tile {
             texture: &texture,
             src,
+            trim: None,
         };
 
         let mut renderer = MockCanvas::new();
@@ -151,6 +308,7 @@ mod tests {
         let tile = Tile {
             texture: &texture,
             src,
+            trim: None,
         };
 
         let mut renderer = MockCanvas::new();
@@ -158,4 +316,88 @@ mod tests {
         assert_eq!(renderer.draw.len(), 1);
         assert_eq!(renderer.draw[0], (texture, options::from(src)));
     }
+
+    #[test]
+    fn grid_tile_size() {
+        let texture = MockTexture {
+            dims: glm::uvec2(20, 10),
+        };
+        let sheet = TileSheet::from_grid(Grid::new(glm::uvec2(5, 5)), texture).unwrap();
+        let tile = sheet.tile(5);
+        assert_eq!(*tile.texture, texture);
+        assert_eq!(tile.src, glm::uvec4(5, 5, 5, 5));
+    }
+
+    #[test]
+    fn grid_margin_spacing() {
+        // 2px margin, 1px spacing, 3 columns of 4x4 tiles: 2 + 4 + 1 + 4 + 1 + 4 + 2
+        let texture = MockTexture {
+            dims: glm::uvec2(18, 18),
+        };
+        let grid = Grid::new(glm::uvec2(4, 4)).margin(2).spacing(1);
+        let sheet = TileSheet::from_grid(grid, texture).unwrap();
+        assert_eq!(sheet.tile(0).src, glm::uvec4(2, 2, 4, 4));
+        assert_eq!(sheet.tile(2).src, glm::uvec4(12, 2, 4, 4));
+        assert_eq!(sheet.tile(4).src, glm::uvec4(7, 7, 4, 4));
+    }
+
+    #[test]
+    fn rejects_grids_without_columns() {
+        let texture = MockTexture {
+            dims: glm::uvec2(18, 18),
+        };
+        assert!(TileSheet::from_grid(Grid::new(glm::uvec2(0, 4)), texture).is_err());
+        let too_wide = Grid::new(glm::uvec2(16, 4)).margin(2);
+        assert!(TileSheet::from_grid(too_wide, texture).is_err());
+    }
+
+    #[test]
+    fn frames() {
+        let texture = MockTexture {
+            dims: glm::uvec2(20, 10),
+        };
+        let frames = vec![
+            Frame {
+                src: glm::uvec4(0, 0, 7, 10),
+                trim: None,
+            },
+            Frame {
+                src: glm::uvec4(7, 2, 13, 4),
+                trim: None,
+            },
+        ];
+        let sheet = TileSheet::from_frames(frames, texture).unwrap();
+        let tile = sheet.tile(1);
+        assert_eq!(*tile.texture, texture);
+        assert_eq!(tile.src, glm::uvec4(7, 2, 13, 4));
+        assert_eq!(tile.dims(), glm::uvec2(13, 4));
+        assert_eq!(sheet.tile(2).src, glm::uvec4(0, 0, 7, 10));
+
+        assert!(TileSheet::from_frames(vec![], texture).is_err());
+    }
+
+    #[test]
+    fn draws_trimmed_tiles() {
+        let src = glm::uvec4(4, 0, 6, 4);
+        let texture = MockTexture {
+            dims: glm::uvec2(20, 10),
+        };
+
+        let tile = Tile {
+            texture: &texture,
+            src,
+            trim: Some(Trim {
+                offset: glm::uvec2(2, 3),
+                source: glm::uvec2(10, 10),
+            }),
+        };
+        assert_eq!(tile.dims(), glm::uvec2(10, 10));
+
+        let mut renderer = MockCanvas::new();
+        let dst = align::left(10).top(20).dims(glm::uvec2(20, 20));
+        assert!(renderer.draw(&tile, options::at(dst)).is_ok());
+        assert_eq!(renderer.draw.len(), 1);
+        let trimmed = align::left(14).top(26).dims(glm::uvec2(12, 8));
+        assert_eq!(renderer.draw[0], (texture, options::at(trimmed).from(src)));
+    }
 }
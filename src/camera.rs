@@ -0,0 +1,387 @@
+use crate::{
+    renderer::{options::Rotation, Destination, FDestination, Options, Transform, Transformed},
+    shape::{rotate, Rectangle},
+};
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    /// World position shown at the center of the viewport
+    pub position: glm::DVec2,
+    pub zoom: f64,
+    /// Degrees, clockwise, that the world appears rotated on screen
+    pub rotation: f64,
+    pub viewport: glm::UVec2,
+    /// World area the viewport is kept within
+    pub bounds: Option<Rectangle>,
+    shake: Option<Shake>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Shake {
+    magnitude: f64,
+    duration: Duration,
+    elapsed: Duration,
+}
+
+impl Camera {
+    pub fn new(viewport: glm::UVec2) -> Self {
+        Camera {
+            position: glm::to_dvec2(viewport) / 2.,
+            zoom: 1.,
+            rotation: 0.,
+            viewport,
+            bounds: None,
+            shake: None,
+        }
+    }
+
+    pub fn center_on(&mut self, target: glm::DVec2) {
+        self.position = target;
+        self.clamp();
+    }
+
+    /// Eases towards `target`, closing the gap faster the higher `rate` is
+    pub fn follow(&mut self, target: glm::DVec2, rate: f64, elapsed: Duration) {
+        let t = 1. - (-rate * elapsed.as_secs_f64()).exp();
+        self.position = self.position + (target - self.position) * t;
+        self.clamp();
+    }
+
+    /// Shakes the view by up to `magnitude` world units, fading out over
+    /// `duration`; a zero duration stops any shaking
+    pub fn shake(&mut self, magnitude: f64, duration: Duration) {
+        self.shake = if duration > Duration::default() {
+            Some(Shake {
+                magnitude,
+                duration,
+                elapsed: Duration::default(),
+            })
+        } else {
+            None
+        };
+    }
+
+    pub fn update(&mut self, elapsed: Duration) {
+        if let Some(mut shake) = self.shake {
+            shake.elapsed += elapsed;
+            self.shake = if shake.elapsed < shake.duration {
+                Some(shake)
+            } else {
+                None
+            };
+        }
+    }
+
+    pub fn to_screen(&self, world: glm::DVec2) -> glm::DVec2 {
+        let relative = (world - self.eye()) * self.zoom;
+        rotate(relative, self.rotation) + glm::to_dvec2(self.viewport) / 2.
+    }
+
+    /// Converts screen coordinates, such as `input::State::mouse_coords`,
+    /// into world coordinates
+    pub fn to_world(&self, screen: glm::IVec2) -> glm::DVec2 {
+        let relative = glm::to_dvec2(screen) - glm::to_dvec2(self.viewport) / 2.;
+        rotate(relative, -self.rotation) / self.zoom + self.eye()
+    }
+
+    /// Bounding box of the world area currently in view
+    pub fn visible(&self) -> Rectangle {
+        let half = self.half_extents();
+        Rectangle {
+            top_left: self.eye() - half,
+            dims: half * 2.,
+        }
+    }
+
+    /// Wraps a renderer so that destinations are given in world coordinates
+    pub fn renderer<'a, R>(&self, renderer: &'a mut R) -> Transformed<'a, R, Camera> {
+        Transformed::new(renderer, *self)
+    }
+
+    /// The clamp is redone here so that changes to the zoom, rotation or
+    /// bounds since the last move are kept within the bounds too
+    fn eye(&self) -> glm::DVec2 {
+        self.clamped(self.position) + self.shake.map_or(glm::dvec2(0., 0.), |s| s.offset())
+    }
+
+    /// Half the size of the axis aligned world area in view
+    fn half_extents(&self) -> glm::DVec2 {
+        let half = glm::to_dvec2(self.viewport) / (2. * self.zoom);
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        glm::dvec2(
+            half.x * cos.abs() + half.y * sin.abs(),
+            half.x * sin.abs() + half.y * cos.abs(),
+        )
+    }
+
+    fn clamp(&mut self) {
+        self.position = self.clamped(self.position);
+    }
+
+    fn clamped(&self, position: glm::DVec2) -> glm::DVec2 {
+        let bounds = match self.bounds {
+            Some(bounds) => bounds,
+            None => return position,
+        };
+        let half = self.half_extents();
+        let clamp = |pos: f64, min: f64, len: f64, half: f64| {
+            if len <= half * 2. {
+                min + len / 2.
+            } else {
+                pos.max(min + half).min(min + len - half)
+            }
+        };
+        glm::dvec2(
+            clamp(position.x, bounds.top_left.x, bounds.dims.x, half.x),
+            clamp(position.y, bounds.top_left.y, bounds.dims.y, half.y),
+        )
+    }
+}
+
+impl Shake {
+    fn offset(&self) -> glm::DVec2 {
+        let t = self.elapsed.as_secs_f64();
+        let fade = 1. - t / self.duration.as_secs_f64();
+        glm::dvec2((t * 47.).sin(), (t * 53.).cos()) * self.magnitude * fade
+    }
+}
+
+impl Transform for Camera {
+    fn destination(&self, dst: Destination) -> Destination {
+        self.precise(dst.into()).round()
+    }
+
+    /// Rectangles cannot be rotated so only their centers follow the
+    /// camera's rotation
+    fn precise(&self, dst: FDestination) -> FDestination {
+        let dims = dst.dims * self.zoom;
+        FDestination::new(self.to_screen(dst.center()) - dims / 2., dims)
+    }
+
+    /// Textures are rotated around the same pivot they would use without
+    /// a camera, which is their center unless told otherwise
+    fn options(&self, mut options: Options) -> Options {
+        let precise = options.precise_dst();
+        let dst = match (precise, options.dst) {
+            (Some(fdst), _) => fdst,
+            (None, Some(dst)) => FDestination::from(dst),
+            (None, None) => return options,
+        };
+        let (pivot, angle) = match options.rotation {
+            Some(r) => (glm::to_dvec2(r.center), r.angle),
+            None => (dst.dims / 2., 0.),
+        };
+
+        let pivot_screen = self.to_screen(dst.top_left + pivot);
+        let pivot = pivot * self.zoom;
+        let transformed = FDestination::new(pivot_screen - pivot, dst.dims * self.zoom);
+
+        options = if precise.is_some() {
+            options.at_precise(transformed)
+        } else {
+            options.at(transformed.round())
+        };
+        let angle = angle + self.rotation;
+        if options.rotation.is_some() || angle != 0. {
+            options.rotation = Some(Rotation {
+                angle,
+                center: glm::to_ivec2(glm::round(pivot)),
+            });
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{align, mocks::MockCanvas, options, Renderer},
+        texture::{mocks::MockTexture, Texture},
+    };
+
+    fn assert_close(actual: glm::DVec2, expected: glm::DVec2) {
+        assert!(
+            glm::length(actual - expected) < 0.000_001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn new_shows_screen() {
+        let camera = Camera::new(glm::uvec2(320, 180));
+        assert_close(camera.to_screen(glm::dvec2(10., 20.)), glm::dvec2(10., 20.));
+        assert_close(camera.to_world(glm::ivec2(10, 20)), glm::dvec2(10., 20.));
+    }
+
+    #[test]
+    fn zoomed_and_rotated() {
+        let mut camera = Camera::new(glm::uvec2(200, 100));
+        camera.center_on(glm::dvec2(500., 500.));
+        camera.zoom = 2.;
+        camera.rotation = 90.;
+
+        assert_close(
+            camera.to_screen(glm::dvec2(500., 500.)),
+            glm::dvec2(100., 50.),
+        );
+        assert_close(
+            camera.to_screen(glm::dvec2(510., 500.)),
+            glm::dvec2(100., 70.),
+        );
+        assert_close(camera.to_world(glm::ivec2(100, 70)), glm::dvec2(510., 500.));
+        assert_close(camera.to_world(glm::ivec2(80, 50)), glm::dvec2(500., 510.));
+
+        let visible = camera.visible();
+        assert_close(visible.top_left, glm::dvec2(475., 450.));
+        assert_close(visible.dims, glm::dvec2(50., 100.));
+    }
+
+    #[test]
+    fn clamps_to_bounds() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.bounds = Some(Rectangle {
+            top_left: glm::dvec2(0., 0.),
+            dims: glm::dvec2(400., 80.),
+        });
+        camera.center_on(glm::dvec2(10., 10.));
+        assert_close(camera.position, glm::dvec2(50., 40.));
+
+        camera.center_on(glm::dvec2(390., 10.));
+        assert_close(camera.position, glm::dvec2(350., 40.));
+    }
+
+    #[test]
+    fn clamps_rotated_and_rezoomed_views() {
+        let mut camera = Camera::new(glm::uvec2(100, 50));
+        camera.bounds = Some(Rectangle {
+            top_left: glm::dvec2(0., 0.),
+            dims: glm::dvec2(400., 400.),
+        });
+        camera.rotation = 90.;
+        camera.center_on(glm::dvec2(0., 0.));
+        assert_close(camera.position, glm::dvec2(25., 50.));
+
+        camera.zoom = 0.5;
+        let visible = camera.visible();
+        assert_close(visible.top_left, glm::dvec2(0., 0.));
+        assert_close(visible.dims, glm::dvec2(100., 200.));
+    }
+
+    #[test]
+    fn follows_smoothly() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.center_on(glm::dvec2(0., 0.));
+        camera.follow(glm::dvec2(100., 0.), 2_f64.ln(), Duration::from_secs(1));
+        assert_close(camera.position, glm::dvec2(50., 0.));
+        camera.follow(glm::dvec2(100., 0.), 2_f64.ln(), Duration::from_secs(1));
+        assert_close(camera.position, glm::dvec2(75., 0.));
+    }
+
+    #[test]
+    fn shakes_then_settles() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.shake(4., Duration::from_millis(500));
+        camera.update(Duration::from_millis(100));
+        let offset = camera.to_screen(camera.position) - glm::dvec2(50., 50.);
+        assert!(glm::length(offset) > 0.);
+        assert!(glm::length(offset) <= 4.);
+
+        camera.update(Duration::from_millis(400));
+        assert_close(camera.to_screen(camera.position), glm::dvec2(50., 50.));
+    }
+
+    #[test]
+    fn ignores_instant_shakes() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.shake(4., Duration::default());
+        assert_close(camera.to_screen(camera.position), glm::dvec2(50., 50.));
+    }
+
+    #[test]
+    fn draws_in_world_space() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.center_on(glm::dvec2(200., 200.));
+        camera.zoom = 2.;
+
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 6),
+        };
+        let image = texture.at(align::left(200).top(190));
+
+        let mut canvas = MockCanvas::new();
+        assert!(camera.renderer(&mut canvas).show(&image).is_ok());
+        let dst = align::left(50).top(30).dims(glm::uvec2(20, 12));
+        assert_eq!(canvas.draw, vec![(texture, options::at(dst))]);
+    }
+
+    #[test]
+    fn rotates_around_pivot() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.center_on(glm::dvec2(0., 0.));
+        camera.rotation = 90.;
+
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        let rotation = Rotation {
+            angle: 30.,
+            center: glm::ivec2(0, 0),
+        };
+        let dst = align::left(20).top(0).dims(texture.dims);
+
+        let mut canvas = MockCanvas::new();
+        let result = camera
+            .renderer(&mut canvas)
+            .draw(&texture, options::at(dst).rotate(rotation));
+        assert!(result.is_ok());
+
+        let rotation = Rotation {
+            angle: 120.,
+            center: glm::ivec2(0, 0),
+        };
+        let dst = align::left(50).top(70).dims(texture.dims);
+        assert_eq!(
+            canvas.draw,
+            vec![(texture, options::at(dst).rotate(rotation))]
+        );
+    }
+
+    #[test]
+    fn transforms_rects() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.center_on(glm::dvec2(0., 0.));
+        camera.zoom = 0.5;
+
+        let mut canvas = MockCanvas::new();
+        let rect = align::center(20).middle(-40).dims(glm::uvec2(8, 4));
+        assert!(camera.renderer(&mut canvas).fill_rects(&[rect]).is_ok());
+        let expected = align::left(58).top(29).dims(glm::uvec2(4, 2));
+        assert_eq!(canvas.fill, vec![expected]);
+    }
+
+    #[test]
+    fn keeps_sub_pixel_precision() {
+        let mut camera = Camera::new(glm::uvec2(100, 100));
+        camera.center_on(glm::dvec2(0., 0.));
+        camera.zoom = 4.;
+
+        let texture = MockTexture {
+            dims: glm::uvec2(2, 2),
+        };
+        let dst = FDestination::new(glm::dvec2(0.25, -0.5), glm::dvec2(2., 2.));
+
+        let mut canvas = MockCanvas::new();
+        let result = camera
+            .renderer(&mut canvas)
+            .draw(&texture, options::at_precise(dst));
+        assert!(result.is_ok());
+
+        let expected = FDestination::new(glm::dvec2(51., 48.), glm::dvec2(8., 8.));
+        assert_eq!(canvas.draw, vec![(texture, options::at_precise(expected))]);
+    }
+}
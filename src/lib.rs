@@ -1,12 +1,16 @@
 pub mod animation;
+pub mod camera;
 pub mod engine;
 pub mod font;
 pub mod input;
+pub mod particles;
+pub mod physics;
 pub mod renderer;
 pub mod resource;
 pub mod sdl2_helpers;
 pub mod shape;
 pub mod texture;
+pub mod tilemap;
 pub mod timer;
 pub mod window_wrapper;
 
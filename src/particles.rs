@@ -0,0 +1,399 @@
+use crate::{
+    renderer::{options::Rotation, ColorRGBA, Draw, FDestination, Options, Renderer, Show},
+    texture::Texture,
+    Result,
+};
+
+use std::time::Duration;
+
+/// Values particles are spawned with, picked uniformly between `min` and
+/// `max`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range<T> {
+    pub min: T,
+    pub max: T,
+}
+
+impl<T: Copy> Range<T> {
+    pub fn new(min: T, max: T) -> Self {
+        Range { min, max }
+    }
+
+    pub fn exactly(value: T) -> Self {
+        Range::new(value, value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// Particles spawned per second while emitting
+    pub rate: f64,
+    /// Most particles alive at once; the pool is allocated up front
+    pub capacity: usize,
+    pub lifetime: Range<Duration>,
+    pub velocity: Range<glm::DVec2>,
+    pub acceleration: Range<glm::DVec2>,
+    /// Degrees, clockwise, that particles start rotated by
+    pub rotation: Range<f64>,
+    /// Degrees per second
+    pub spin: Range<f64>,
+    /// Color and alpha at the start and end of each particle's life
+    pub color: (ColorRGBA, ColorRGBA),
+    /// Scale of the sprite at the start and end of each particle's life
+    pub size: (f64, f64),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let white = ColorRGBA(255, 255, 255, 255);
+        Config {
+            rate: 0.,
+            capacity: 256,
+            lifetime: Range::exactly(Duration::from_secs(1)),
+            velocity: Range::exactly(glm::dvec2(0., 0.)),
+            acceleration: Range::exactly(glm::dvec2(0., 0.)),
+            rotation: Range::exactly(0.),
+            spin: Range::exactly(0.),
+            color: (white, white),
+            size: (1., 1.),
+        }
+    }
+}
+
+impl Config {
+    pub fn rate(mut self, rate: f64) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn lifetime(mut self, lifetime: Range<Duration>) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    pub fn velocity(mut self, velocity: Range<glm::DVec2>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn acceleration(mut self, acceleration: Range<glm::DVec2>) -> Self {
+        self.acceleration = acceleration;
+        self
+    }
+
+    pub fn rotation(mut self, rotation: Range<f64>) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn spin(mut self, spin: Range<f64>) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    pub fn color(mut self, start: ColorRGBA, end: ColorRGBA) -> Self {
+        self.color = (start, end);
+        self
+    }
+
+    pub fn size(mut self, start: f64, end: f64) -> Self {
+        self.size = (start, end);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: glm::DVec2,
+    pub velocity: glm::DVec2,
+    pub acceleration: glm::DVec2,
+    pub rotation: f64,
+    pub spin: f64,
+    pub age: Duration,
+    pub lifetime: Duration,
+}
+
+impl Particle {
+    /// How far along its life the particle is, from 0 to 1
+    pub fn progress(&self) -> f64 {
+        if self.lifetime == Duration::default() {
+            1.
+        } else {
+            self.age.as_secs_f64() / self.lifetime.as_secs_f64()
+        }
+    }
+}
+
+/// Spawns, moves and draws particles using `sprite`, which can be a texture
+/// or a `Tile`
+pub struct Emitter<S> {
+    pub sprite: S,
+    pub config: Config,
+    pub position: glm::DVec2,
+    pub emitting: bool,
+    /// Pooled particles; only the first `alive` are in use
+    pool: Vec<Particle>,
+    alive: usize,
+    pending: f64,
+    random: Random,
+}
+
+impl<S> Emitter<S> {
+    pub fn new(sprite: S, config: Config) -> Self {
+        let idle = Particle {
+            position: glm::dvec2(0., 0.),
+            velocity: glm::dvec2(0., 0.),
+            acceleration: glm::dvec2(0., 0.),
+            rotation: 0.,
+            spin: 0.,
+            age: Duration::default(),
+            lifetime: Duration::default(),
+        };
+        Emitter {
+            sprite,
+            pool: vec![idle; config.capacity],
+            config,
+            position: glm::dvec2(0., 0.),
+            emitting: true,
+            alive: 0,
+            pending: 0.,
+            random: Random(0x2545_f491_4f6c_dd1d),
+        }
+    }
+
+    pub fn at(mut self, position: glm::DVec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Seeds the random values particles spawn with
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.random = Random(seed.max(1));
+        self
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.pool[..self.alive]
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive == 0
+    }
+
+    /// Spawns up to `count` particles at once, ignoring the rate
+    pub fn burst(&mut self, count: usize) {
+        (0..count).for_each(|_| self.spawn());
+    }
+
+    pub fn update(&mut self, elapsed: Duration) {
+        let delta = elapsed.as_secs_f64();
+        let mut i = 0;
+        while i < self.alive {
+            let particle = &mut self.pool[i];
+            particle.age += elapsed;
+            if particle.age >= particle.lifetime {
+                self.alive -= 1;
+                self.pool.swap(i, self.alive);
+                continue;
+            }
+            particle.velocity = particle.velocity + particle.acceleration * delta;
+            particle.position = particle.position + particle.velocity * delta;
+            particle.rotation += particle.spin * delta;
+            i += 1;
+        }
+
+        if self.emitting {
+            self.pending += self.config.rate * delta;
+            let count = self.pending.floor();
+            self.pending -= count;
+            self.burst(count as usize);
+        }
+    }
+
+    fn spawn(&mut self) {
+        if self.alive == self.pool.len() {
+            return;
+        }
+        let config = &self.config;
+        let random = &mut self.random;
+        let lifetime = config.lifetime;
+        let lifetime =
+            lifetime.min + (lifetime.max.saturating_sub(lifetime.min)).mul_f64(random.unit());
+        self.pool[self.alive] = Particle {
+            position: self.position,
+            velocity: random.vector(config.velocity),
+            acceleration: random.vector(config.acceleration),
+            rotation: random.between(config.rotation),
+            spin: random.between(config.spin),
+            age: Duration::default(),
+            lifetime,
+        };
+        self.alive += 1;
+    }
+
+    fn options(&self, particle: &Particle, dims: glm::UVec2) -> Options {
+        let t = particle.progress();
+        let (start, end) = self.config.size;
+        let dims = glm::to_dvec2(dims) * (start + (end - start) * t);
+        let dst = FDestination::new(particle.position - dims / 2., dims);
+        let options = Options::default()
+            .at_precise(dst)
+            .color(lerp(self.config.color, t));
+        if particle.rotation == 0. {
+            options
+        } else {
+            options.rotate(Rotation {
+                angle: particle.rotation,
+                center: glm::to_ivec2(glm::round(dims / 2.)),
+            })
+        }
+    }
+}
+
+fn lerp((start, end): (ColorRGBA, ColorRGBA), t: f64) -> ColorRGBA {
+    let channel = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+    ColorRGBA(
+        channel(start.0, end.0),
+        channel(start.1, end.1),
+        channel(start.2, end.2),
+        channel(start.3, end.3),
+    )
+}
+
+/// Small xorshift generator; particles only need cheap, repeatable noise
+#[derive(Debug, Clone, Copy)]
+struct Random(u64);
+
+impl Random {
+    fn unit(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn between(&mut self, range: Range<f64>) -> f64 {
+        range.min + (range.max - range.min) * self.unit()
+    }
+
+    fn vector(&mut self, range: Range<glm::DVec2>) -> glm::DVec2 {
+        let x = self.between(Range::new(range.min.x, range.max.x));
+        let y = self.between(Range::new(range.min.y, range.max.y));
+        glm::dvec2(x, y)
+    }
+}
+
+impl<R: Renderer, S: Texture + Draw<R>> Show<R> for Emitter<S> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        let dims = self.sprite.dims();
+        self.particles()
+            .iter()
+            .try_for_each(|p| renderer.draw(&self.sprite, self.options(p, dims)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{renderer::mocks::MockCanvas, texture::mocks::MockTexture};
+
+    fn emitter(config: Config) -> Emitter<MockTexture> {
+        let texture = MockTexture {
+            dims: glm::uvec2(4, 4),
+        };
+        Emitter::new(texture, config)
+    }
+
+    #[test]
+    fn spawns_at_rate() {
+        let mut emitter = emitter(Config::default().rate(10.));
+        emitter.update(Duration::from_millis(250));
+        assert_eq!(emitter.len(), 2);
+        emitter.update(Duration::from_millis(250));
+        assert_eq!(emitter.len(), 5);
+
+        emitter.emitting = false;
+        emitter.update(Duration::from_millis(500));
+        assert_eq!(emitter.len(), 5);
+    }
+
+    #[test]
+    fn pools_particles() {
+        let mut emitter = emitter(Config::default().capacity(8));
+        emitter.burst(20);
+        assert_eq!(emitter.len(), 8);
+        assert_eq!(emitter.pool.capacity(), 8);
+
+        emitter.update(Duration::from_secs(1));
+        assert!(emitter.is_empty());
+        emitter.burst(3);
+        assert_eq!(emitter.len(), 3);
+        assert_eq!(emitter.pool.capacity(), 8);
+    }
+
+    #[test]
+    fn expires_by_lifetime() {
+        let lifetime = Range::new(Duration::from_millis(100), Duration::from_millis(300));
+        let mut emitter = emitter(Config::default().lifetime(lifetime)).seed(7);
+        emitter.burst(50);
+        assert!(emitter
+            .particles()
+            .iter()
+            .all(|p| p.lifetime >= lifetime.min && p.lifetime <= lifetime.max));
+
+        emitter.update(Duration::from_millis(200));
+        assert!(!emitter.is_empty());
+        assert!(emitter.len() < 50);
+        assert!(emitter
+            .particles()
+            .iter()
+            .all(|p| p.lifetime > Duration::from_millis(200)));
+
+        emitter.update(Duration::from_millis(100));
+        assert!(emitter.is_empty());
+    }
+
+    #[test]
+    fn moves_particles() {
+        let config = Config::default()
+            .velocity(Range::exactly(glm::dvec2(10., 0.)))
+            .acceleration(Range::exactly(glm::dvec2(0., 20.)))
+            .spin(Range::exactly(90.));
+        let mut emitter = emitter(config).at(glm::dvec2(5., 5.));
+        emitter.burst(1);
+        emitter.update(Duration::from_millis(500));
+
+        let particle = emitter.particles()[0];
+        assert_eq!(particle.position, glm::dvec2(10., 10.));
+        assert_eq!(particle.velocity, glm::dvec2(10., 10.));
+        assert_eq!(particle.rotation, 45.);
+    }
+
+    #[test]
+    fn fades_and_grows_over_life() {
+        let config = Config::default()
+            .lifetime(Range::exactly(Duration::from_secs(2)))
+            .color(ColorRGBA(255, 0, 0, 255), ColorRGBA(0, 0, 255, 0))
+            .size(1., 3.);
+        let mut emitter = emitter(config).at(glm::dvec2(20., 20.));
+        emitter.burst(1);
+        emitter.update(Duration::from_secs(1));
+
+        let mut canvas = MockCanvas::new();
+        assert!(canvas.show(&emitter).is_ok());
+        let (_, options) = &canvas.draw[0];
+        assert_eq!(options.color, Some(ColorRGBA(128, 0, 128, 128)));
+        let dst = FDestination::new(glm::dvec2(16., 16.), glm::dvec2(8., 8.));
+        assert_eq!(options.precise_dst(), Some(dst));
+        assert_eq!(options.rotation, None);
+    }
+}
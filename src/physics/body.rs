@@ -0,0 +1,87 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Kind {
+    /// Never moves, e.g. walls and floors
+    Static,
+    /// Moves by its velocity but is not pushed by gravity or collisions,
+    /// e.g. moving platforms
+    Kinematic,
+    /// Pushed by gravity and collisions
+    #[default]
+    Dynamic,
+}
+
+/// Layers and mask that match everything
+pub const ALL: u32 = u32::MAX;
+
+#[derive(Debug, Clone)]
+pub struct Body<S> {
+    pub shape: S,
+    pub kind: Kind,
+    pub velocity: glm::DVec2,
+    pub mass: f64,
+    /// Bounciness from 0, which stops dead, to 1, which keeps its speed
+    pub restitution: f64,
+    pub friction: f64,
+    /// Bits of the layers this body is on
+    pub layers: u32,
+    /// Bits of the layers this body collides with
+    pub mask: u32,
+}
+
+impl<S> Body<S> {
+    pub fn new(shape: S, kind: Kind) -> Self {
+        Body {
+            shape,
+            kind,
+            velocity: glm::dvec2(0., 0.),
+            mass: 1.,
+            restitution: 0.,
+            friction: 0.,
+            layers: 1,
+            mask: ALL,
+        }
+    }
+
+    pub fn velocity(mut self, velocity: glm::DVec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn mass(mut self, mass: f64) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn restitution(mut self, restitution: f64) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn friction(mut self, friction: f64) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn layers(mut self, layers: u32) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    pub fn mask(mut self, mask: u32) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Zero for bodies that collisions cannot move
+    pub fn inverse_mass(&self) -> f64 {
+        match self.kind {
+            Kind::Dynamic if self.mass > 0. => 1. / self.mass,
+            _ => 0.,
+        }
+    }
+
+    /// Whether the layers and masks of both bodies let them collide
+    pub fn collides_with<T>(&self, other: &Body<T>) -> bool {
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+}
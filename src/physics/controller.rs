@@ -0,0 +1,366 @@
+use crate::shape::{Intersect, Rectangle, Segment, Shape};
+
+use std::time::Duration;
+
+/// How much a point may sink into a surface and still count as on top of it
+const EPSILON: f64 = 1e-6;
+
+/// Static level geometry a `Controller` moves through
+#[derive(Debug, Clone, Copy)]
+pub enum Terrain {
+    /// Blocks from every side
+    Solid(Rectangle),
+    /// Only blocks from above, so it can be jumped through from below
+    OneWay(Rectangle),
+    /// Floor that the bottom center of the character walks along, for ramps
+    /// and hills; like `OneWay`, it only blocks from above
+    Slope(Segment),
+}
+
+/// Sides of the character that touched terrain during the last move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Contacts {
+    pub ground: bool,
+    pub ceiling: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+/// Kinematic platformer character that moves a `Rectangle` one axis at a
+/// time so that walls, floors and ceilings each stop it cleanly
+#[derive(Debug, Clone)]
+pub struct Controller {
+    pub body: Rectangle,
+    pub velocity: glm::DVec2,
+    pub gravity: glm::DVec2,
+    /// How long after walking off a ledge the character can still jump
+    pub coyote: Duration,
+    /// How far floors are stuck to while walking, so that the character runs
+    /// down slopes and over their bends instead of taking off
+    pub snap: f64,
+    /// Falls through `OneWay` platforms and slopes while set
+    pub drop_through: bool,
+    contacts: Contacts,
+    /// Time since last on the ground; `None` once a jump used it up
+    airborne: Option<Duration>,
+}
+
+impl Controller {
+    pub fn new(body: Rectangle) -> Self {
+        Controller {
+            body,
+            velocity: glm::dvec2(0., 0.),
+            gravity: glm::dvec2(0., 0.),
+            coyote: Duration::from_millis(100),
+            snap: 0.,
+            drop_through: false,
+            contacts: Contacts::default(),
+            airborne: None,
+        }
+    }
+
+    pub fn gravity(mut self, gravity: glm::DVec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn coyote(mut self, coyote: Duration) -> Self {
+        self.coyote = coyote;
+        self
+    }
+
+    pub fn snap(mut self, snap: f64) -> Self {
+        self.snap = snap;
+        self
+    }
+
+    pub fn contacts(&self) -> Contacts {
+        self.contacts
+    }
+
+    pub fn is_grounded(&self) -> bool {
+        self.contacts.ground
+    }
+
+    pub fn can_jump(&self) -> bool {
+        self.airborne
+            .is_some_and(|airborne| airborne <= self.coyote)
+    }
+
+    /// Jumps at `speed` if the character is on, or just left, the ground
+    pub fn jump(&mut self, speed: f64) -> bool {
+        let jumped = self.can_jump();
+        if jumped {
+            self.velocity.y = -speed;
+            self.airborne = None;
+        }
+        jumped
+    }
+
+    /// Applies gravity, moves by the velocity and stops the velocity against
+    /// anything that was hit
+    pub fn update(&mut self, terrain: &[Terrain], elapsed: Duration) -> Contacts {
+        let delta = elapsed.as_secs_f64();
+        self.velocity = self.velocity + self.gravity * delta;
+        let contacts = self.move_by(self.velocity * delta, terrain);
+
+        if (contacts.ground && self.velocity.y > 0.) || (contacts.ceiling && self.velocity.y < 0.) {
+            self.velocity.y = 0.;
+        }
+        if (contacts.left && self.velocity.x < 0.) || (contacts.right && self.velocity.x > 0.) {
+            self.velocity.x = 0.;
+        }
+        self.airborne = if contacts.ground {
+            Some(Duration::default())
+        } else {
+            self.airborne.map(|airborne| airborne + elapsed)
+        };
+        contacts
+    }
+
+    /// Moves horizontally and then vertically, stopping at terrain
+    pub fn move_by(&mut self, motion: glm::DVec2, terrain: &[Terrain]) -> Contacts {
+        let was_grounded = self.contacts.ground;
+        let mut contacts = Contacts::default();
+
+        self.body = self.body.nudge(glm::dvec2(motion.x, 0.));
+        for solid in terrain.iter().filter_map(|t| match t {
+            Terrain::Solid(solid) => Some(solid),
+            _ => None,
+        }) {
+            if self.body.mtv(solid).is_none() {
+                continue;
+            }
+            if motion.x > 0. {
+                self.body.top_left.x = solid.top_left.x - self.body.dims.x;
+                contacts.right = true;
+            } else if motion.x < 0. {
+                self.body.top_left.x = solid.top_left.x + solid.dims.x;
+                contacts.left = true;
+            }
+        }
+
+        let before = self.body;
+        self.body = self.body.nudge(glm::dvec2(0., motion.y));
+        for piece in terrain {
+            match piece {
+                Terrain::Solid(solid) => {
+                    if self.body.mtv(solid).is_none() {
+                        continue;
+                    }
+                    if motion.y > 0. {
+                        self.land_on(solid.top_left.y);
+                        contacts.ground = true;
+                    } else if motion.y < 0. {
+                        self.body.top_left.y = solid.top_left.y + solid.dims.y;
+                        contacts.ceiling = true;
+                    }
+                }
+                Terrain::OneWay(platform) => {
+                    let top = platform.top_left.y;
+                    let falling_onto = motion.y >= 0. && bottom(&before) <= top + EPSILON;
+                    if !self.drop_through && falling_onto && self.body.mtv(platform).is_some() {
+                        self.land_on(top);
+                        contacts.ground = true;
+                    }
+                }
+                Terrain::Slope(slope) => {
+                    if self.drop_through || motion.y < 0. {
+                        continue;
+                    }
+                    let foot = self.body.center().x;
+                    let (surface, gradient) = match height(slope, foot) {
+                        Some(height) => height,
+                        None => continue,
+                    };
+                    let reach = motion.x.abs() * gradient + EPSILON;
+                    if bottom(&self.body) >= surface && bottom(&before) <= surface + reach {
+                        self.land_on(surface);
+                        contacts.ground = true;
+                    }
+                }
+            }
+        }
+
+        if was_grounded && !contacts.ground && motion.y >= 0. {
+            if let Some(floor) = self.floor(terrain) {
+                self.land_on(floor);
+                contacts.ground = true;
+            }
+        }
+
+        self.contacts = contacts;
+        contacts
+    }
+
+    /// Highest floor within `snap` of the bottom of the character
+    fn floor(&self, terrain: &[Terrain]) -> Option<f64> {
+        let foot = self.body.center().x;
+        let bottom = bottom(&self.body);
+        let beneath = |floor: &Rectangle| {
+            let (left, right) = (floor.top_left.x, floor.top_left.x + floor.dims.x);
+            let spans =
+                left < self.body.top_left.x + self.body.dims.x && right > self.body.top_left.x;
+            Some(floor.top_left.y).filter(|&top| spans && top >= bottom - EPSILON)
+        };
+
+        terrain
+            .iter()
+            .filter_map(|piece| match piece {
+                Terrain::Solid(floor) => beneath(floor),
+                Terrain::OneWay(floor) if !self.drop_through => beneath(floor),
+                Terrain::Slope(slope) if !self.drop_through => {
+                    height(slope, foot).map(|(surface, _)| surface)
+                }
+                _ => None,
+            })
+            .filter(|top| (top - bottom).abs() <= self.snap)
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    fn land_on(&mut self, top: f64) {
+        self.body.top_left.y = top - self.body.dims.y;
+    }
+}
+
+fn bottom(rectangle: &Rectangle) -> f64 {
+    rectangle.top_left.y + rectangle.dims.y
+}
+
+/// Height of `slope` at `x` along with how steep it is, if `x` is over it
+fn height(slope: &Segment, x: f64) -> Option<(f64, f64)> {
+    let (start, end) = (slope.start, slope.end);
+    let run = end.x - start.x;
+    if run == 0. || x < start.x.min(end.x) || x > start.x.max(end.x) {
+        return None;
+    }
+    let rise = end.y - start.y;
+    Some((start.y + rise * (x - start.x) / run, (rise / run).abs()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(10);
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Rectangle {
+        Rectangle {
+            top_left: glm::dvec2(x, y),
+            dims: glm::dvec2(w, h),
+        }
+    }
+
+    fn player(x: f64, y: f64) -> Controller {
+        Controller::new(rect(x, y, 10., 20.)).gravity(glm::dvec2(0., 1000.))
+    }
+
+    fn run(controller: &mut Controller, terrain: &[Terrain], frames: usize) -> Vec<Contacts> {
+        (0..frames)
+            .map(|_| controller.update(terrain, FRAME))
+            .collect()
+    }
+
+    #[test]
+    fn lands_on_solid_ground() {
+        let terrain = [Terrain::Solid(rect(-100., 100., 200., 50.))];
+        let mut player = player(0., 0.);
+        assert!(!player.is_grounded());
+        run(&mut player, &terrain, 100);
+        assert!(player.is_grounded());
+        assert_eq!(bottom(&player.body), 100.);
+        assert_eq!(player.velocity.y, 0.);
+    }
+
+    #[test]
+    fn stops_at_walls() {
+        let terrain = [
+            Terrain::Solid(rect(-100., 100., 200., 50.)),
+            Terrain::Solid(rect(30., 0., 10., 100.)),
+        ];
+        let mut player = player(0., 80.);
+        player.velocity.x = 500.;
+        let contacts = run(&mut player, &terrain, 20);
+        assert!(contacts.iter().all(|c| c.ground));
+        assert!(contacts.iter().any(|c| c.right));
+        assert_eq!(player.body.top_left.x, 20.);
+        assert_eq!(player.velocity.x, 0.);
+
+        player.velocity.x = -500.;
+        let contacts = run(&mut player, &terrain, 1);
+        assert!(!contacts[0].right && !contacts[0].left);
+    }
+
+    #[test]
+    fn bumps_ceilings() {
+        let terrain = [
+            Terrain::Solid(rect(-100., 100., 200., 50.)),
+            Terrain::Solid(rect(-100., 40., 200., 10.)),
+        ];
+        let mut player = player(0., 80.);
+        run(&mut player, &terrain, 1);
+        assert!(player.jump(600.));
+        let contacts = run(&mut player, &terrain, 10);
+        assert!(contacts.iter().any(|c| c.ceiling));
+        assert!(player.body.top_left.y >= 50.);
+        assert!(player.velocity.y >= 0.);
+    }
+
+    #[test]
+    fn jumps_through_one_way_platforms() {
+        let terrain = [
+            Terrain::Solid(rect(-100., 100., 200., 50.)),
+            Terrain::OneWay(rect(-100., 60., 200., 5.)),
+        ];
+        let mut player = player(0., 80.);
+        run(&mut player, &terrain, 1);
+        assert!(player.jump(600.));
+        let contacts = run(&mut player, &terrain, 200);
+        assert!(contacts.iter().all(|c| !c.ceiling));
+        assert!(player.is_grounded());
+        assert_eq!(bottom(&player.body), 60.);
+
+        player.drop_through = true;
+        run(&mut player, &terrain, 100);
+        assert_eq!(bottom(&player.body), 100.);
+    }
+
+    #[test]
+    fn walks_up_and_down_slopes() {
+        let terrain = [
+            Terrain::Solid(rect(-100., 100., 100., 50.)),
+            Terrain::Slope(Segment::new(glm::dvec2(0., 100.), glm::dvec2(100., 50.))),
+            Terrain::Slope(Segment::new(glm::dvec2(100., 50.), glm::dvec2(200., 50.))),
+        ];
+        let mut player = player(-20., 80.).snap(2.);
+        run(&mut player, &terrain, 1);
+
+        player.velocity.x = 200.;
+        let contacts = run(&mut player, &terrain, 100);
+        assert!(contacts.iter().all(|c| c.ground && !c.right));
+        assert_eq!(bottom(&player.body), 50.);
+
+        player.velocity.x = -200.;
+        let contacts = run(&mut player, &terrain, 100);
+        assert!(contacts.iter().all(|c| c.ground && !c.left));
+        assert_eq!(bottom(&player.body), 100.);
+    }
+
+    #[test]
+    fn allows_coyote_jumps() {
+        let terrain = [Terrain::Solid(rect(-100., 100., 100., 50.))];
+        let mut player = player(-15., 80.).coyote(Duration::from_millis(50));
+        run(&mut player, &terrain, 1);
+        assert!(player.can_jump());
+
+        player.velocity.x = 1000.;
+        run(&mut player, &terrain, 2);
+        assert!(!player.is_grounded());
+        assert!(player.can_jump());
+
+        player.velocity.x = 0.;
+        run(&mut player, &terrain, 5);
+        assert!(!player.can_jump());
+        assert!(!player.jump(600.));
+    }
+}
@@ -0,0 +1,467 @@
+mod body;
+mod controller;
+
+pub use self::{
+    body::{Body, Kind, ALL},
+    controller::{Contacts, Controller, Terrain},
+};
+
+use crate::{
+    engine::World,
+    input,
+    shape::{Grid, Intersect, Shape},
+    State,
+};
+
+use std::{collections::HashMap, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub a: BodyId,
+    pub b: BodyId,
+    /// Unit vector pointing from `b` towards `a`, as first found in a step
+    pub normal: glm::DVec2,
+    /// How far the bodies were pushed apart over every pass of a step
+    pub depth: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Began(BodyId, BodyId),
+    Ended(BodyId, BodyId),
+}
+
+/// Bodies that move and collide with each other. Call `step` from
+/// `engine::World::update` so that it runs with the fixed timestep of
+/// `FixedUpdate`, or run a bare `Space` as the world itself; the ids of
+/// removed bodies are reused.
+pub struct Space<S> {
+    pub gravity: glm::DVec2,
+    /// Collision passes per step; more keep stacks steadier
+    pub iterations: usize,
+    bodies: Vec<Option<Body<S>>>,
+    free: Vec<usize>,
+    contacts: Vec<Contact>,
+    events: Vec<Event>,
+}
+
+impl<S> Space<S> {
+    pub fn new(gravity: glm::DVec2) -> Self {
+        Space {
+            gravity,
+            iterations: 4,
+            bodies: vec![],
+            free: vec![],
+            contacts: vec![],
+            events: vec![],
+        }
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    pub fn insert(&mut self, body: Body<S>) -> BodyId {
+        match self.free.pop() {
+            Some(index) => {
+                self.bodies[index] = Some(body);
+                BodyId(index)
+            }
+            None => {
+                self.bodies.push(Some(body));
+                BodyId(self.bodies.len() - 1)
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: BodyId) -> Option<Body<S>> {
+        let body = self.bodies.get_mut(id.0)?.take()?;
+        self.free.push(id.0);
+        self.contacts.retain(|c| c.a != id && c.b != id);
+        Some(body)
+    }
+
+    pub fn get(&self, id: BodyId) -> Option<&Body<S>> {
+        self.bodies.get(id.0)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: BodyId) -> Option<&mut Body<S>> {
+        self.bodies.get_mut(id.0)?.as_mut()
+    }
+
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyId, &Body<S>)> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_ref().map(|b| (BodyId(i), b)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.bodies.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bodies that touched during the last step
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    /// Contacts that began or ended during the last step
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+}
+
+impl<S: Shape + Intersect<S> + Clone> Space<S> {
+    pub fn step(&mut self, elapsed: Duration) {
+        let delta = elapsed.as_secs_f64();
+        let gravity = self.gravity;
+        for body in self.bodies.iter_mut().flatten() {
+            match body.kind {
+                Kind::Static => continue,
+                Kind::Kinematic => {}
+                Kind::Dynamic => body.velocity = body.velocity + gravity * delta,
+            }
+            body.shape = body.shape.clone().nudge(body.velocity * delta);
+        }
+
+        let previous = std::mem::take(&mut self.contacts);
+        let mut found: HashMap<_, usize> = HashMap::new();
+        for _ in 0..self.iterations {
+            for contact in self.collide() {
+                match found.get(&(contact.a, contact.b)) {
+                    Some(&index) => self.contacts[index].depth += contact.depth,
+                    None => {
+                        found.insert((contact.a, contact.b), self.contacts.len());
+                        self.contacts.push(contact);
+                    }
+                }
+            }
+        }
+
+        let touching =
+            |contacts: &[Contact], c: &Contact| contacts.iter().any(|o| (o.a, o.b) == (c.a, c.b));
+        let began = self
+            .contacts
+            .iter()
+            .filter(|c| !touching(&previous, c))
+            .map(|c| Event::Began(c.a, c.b));
+        let ended = previous
+            .iter()
+            .filter(|c| !touching(&self.contacts, c))
+            .map(|c| Event::Ended(c.a, c.b));
+        self.events = began.chain(ended).collect();
+    }
+
+    /// Finds and resolves every contact between bodies that can collide
+    fn collide(&mut self) -> Vec<Contact> {
+        let mut pairs = self.broadphase().pairs();
+        pairs.sort_by_key(|&(i, j)| (j, i));
+        let mut contacts = vec![];
+        for (i, j) in pairs {
+            let (left, right) = self.bodies.split_at_mut(j);
+            let (a, b) = match (&mut left[i], &mut right[0]) {
+                (Some(a), Some(b)) => (a, b),
+                _ => continue,
+            };
+            if a.inverse_mass() + b.inverse_mass() == 0. || !a.collides_with(b) {
+                continue;
+            }
+            if let Some(mtv) = a.shape.mtv(&b.shape) {
+                let depth = glm::length(mtv);
+                if depth == 0. {
+                    continue;
+                }
+                let normal = mtv / depth;
+                resolve(a, b, normal, depth);
+                contacts.push(Contact {
+                    a: BodyId(i),
+                    b: BodyId(j),
+                    normal,
+                    depth,
+                });
+            }
+        }
+        contacts
+    }
+
+    /// Grid of every body with cells about twice the size of a typical one
+    fn broadphase(&self) -> Grid<usize> {
+        let bounds: Vec<_> = self
+            .bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.as_ref().map(|b| (i, b.shape.aabb())))
+            .collect();
+        let mut extents: Vec<_> = bounds
+            .iter()
+            .map(|(_, r)| r.dims.x.max(r.dims.y) * 2.)
+            .filter(|&e| e > 0. && e.is_finite())
+            .collect();
+        extents.sort_by(f64::total_cmp);
+        let size = extents.get(extents.len() / 2).copied().unwrap_or(1.);
+        let mut grid = Grid::new(size).expect("cell sizes are positive and finite");
+        for (i, r) in bounds {
+            grid.insert(i, r);
+        }
+        grid
+    }
+}
+
+/// Lets a space run on its own as the world of an `Engine`
+impl<S: Shape + Intersect<S> + Clone> World for Space<S> {
+    type Quit = ();
+
+    fn update(mut self, _: &input::State, elapsed: Duration) -> State<Self, ()> {
+        self.step(elapsed);
+        State::Running(self)
+    }
+}
+
+/// Pushes the bodies apart in proportion to their inverse masses and swaps
+/// their momentum along `normal`, losing some to restitution and friction
+fn resolve<S: Shape + Clone>(a: &mut Body<S>, b: &mut Body<S>, normal: glm::DVec2, depth: f64) {
+    let (inverse_a, inverse_b) = (a.inverse_mass(), b.inverse_mass());
+    let inverse = inverse_a + inverse_b;
+    let push = normal * (depth / inverse);
+    a.shape = a.shape.clone().nudge(push * inverse_a);
+    b.shape = b.shape.clone().nudge(push * -inverse_b);
+
+    let relative = a.velocity - b.velocity;
+    let closing = glm::dot(relative, normal);
+    if closing >= 0. {
+        return;
+    }
+    let restitution = a.restitution.min(b.restitution);
+    let impulse = -(1. + restitution) * closing / inverse;
+    a.velocity = a.velocity + normal * (impulse * inverse_a);
+    b.velocity = b.velocity - normal * (impulse * inverse_b);
+
+    let relative = a.velocity - b.velocity;
+    let sliding = relative - normal * glm::dot(relative, normal);
+    let speed = glm::length(sliding);
+    if speed == 0. {
+        return;
+    }
+    let tangent = sliding / speed;
+    let limit = impulse * (a.friction * b.friction).sqrt();
+    let friction = (speed / inverse).min(limit);
+    a.velocity = a.velocity - tangent * (friction * inverse_a);
+    b.velocity = b.velocity + tangent * (friction * inverse_b);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::Rectangle;
+
+    const FRAME: Duration = Duration::from_millis(10);
+
+    fn floor() -> Body<Rectangle> {
+        let shape = Rectangle {
+            top_left: glm::dvec2(-50., 10.),
+            dims: glm::dvec2(100., 10.),
+        };
+        Body::new(shape, Kind::Static)
+    }
+
+    fn crate_at(x: f64, y: f64) -> Body<Rectangle> {
+        let shape = Rectangle {
+            top_left: glm::dvec2(x, y),
+            dims: glm::dvec2(2., 2.),
+        };
+        Body::new(shape, Kind::Dynamic)
+    }
+
+    #[test]
+    fn falls_with_gravity() {
+        let mut space = Space::new(glm::dvec2(0., 10.));
+        let id = space.insert(crate_at(0., 0.));
+        (0..100).for_each(|_| space.step(FRAME));
+        let body = space.get(id).unwrap();
+        assert!((body.velocity.y - 10.).abs() < 0.00001);
+        assert!((body.shape.top_left.y - 5.05).abs() < 0.00001);
+    }
+
+    #[test]
+    fn rests_on_static_bodies() {
+        let mut space = Space::new(glm::dvec2(0., 10.));
+        let floor = space.insert(floor());
+        let id = space.insert(crate_at(0., 6.));
+        (0..200).for_each(|_| space.step(FRAME));
+
+        let body = space.get(id).unwrap();
+        assert!((body.shape.top_left.y - 8.).abs() < 0.01);
+        assert!(body.velocity.y.abs() < 0.2);
+        assert_eq!(space.get(floor).unwrap().shape.top_left.y, 10.);
+        assert_eq!(space.contacts()[0].a, floor);
+        assert_eq!(space.contacts()[0].normal, glm::dvec2(0., 1.));
+    }
+
+    #[test]
+    fn bounces_by_restitution() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        space.insert(floor().restitution(1.));
+        let ball = crate_at(-1., 7.5)
+            .velocity(glm::dvec2(0., 100.))
+            .restitution(0.5);
+        let id = space.insert(ball);
+        space.step(FRAME);
+        assert_eq!(space.get(id).unwrap().velocity, glm::dvec2(0., -50.));
+    }
+
+    #[test]
+    fn pushes_by_mass() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        let light = space.insert(crate_at(0., 0.).mass(1.));
+        let heavy = space.insert(crate_at(1., 0.).mass(3.));
+        space.step(FRAME);
+        let light = space.get(light).unwrap().shape.top_left;
+        let heavy = space.get(heavy).unwrap().shape.top_left;
+        assert!(glm::distance(light, glm::dvec2(-0.75, 0.)) < 0.00001);
+        assert!(glm::distance(heavy, glm::dvec2(1.25, 0.)) < 0.00001);
+    }
+
+    #[test]
+    fn kinematic_bodies_push_dynamic_ones() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        let platform = crate_at(0., 0.).velocity(glm::dvec2(100., 0.));
+        let platform = space.insert(Body {
+            kind: Kind::Kinematic,
+            ..platform
+        });
+        let pushed = space.insert(crate_at(2.5, 0.));
+        space.step(FRAME);
+        assert_eq!(
+            space.get(platform).unwrap().shape.top_left,
+            glm::dvec2(1., 0.)
+        );
+        let pushed = space.get(pushed).unwrap();
+        assert_eq!(pushed.shape.top_left, glm::dvec2(3., 0.));
+        assert_eq!(pushed.velocity, glm::dvec2(100., 0.));
+    }
+
+    #[test]
+    fn friction_slows_sliding() {
+        let mut space = Space::new(glm::dvec2(0., 10.));
+        space.insert(floor().friction(0.5));
+        let slippery = space.insert(crate_at(0., 8.).velocity(glm::dvec2(5., 0.)));
+        let rough = space.insert(crate_at(20., 8.).velocity(glm::dvec2(5., 0.)).friction(0.5));
+        (0..50).for_each(|_| space.step(FRAME));
+        assert_eq!(space.get(slippery).unwrap().velocity.x, 5.);
+        let rough = space.get(rough).unwrap().velocity.x;
+        assert!(rough < 5. && rough > 0., "{}", rough);
+    }
+
+    #[test]
+    fn filters_by_layers() {
+        let mut space = Space::new(glm::dvec2(0., 10.));
+        space.insert(floor().layers(0b10));
+        let ghost = space.insert(crate_at(0., 8.).mask(0b01));
+        let solid = space.insert(crate_at(10., 8.).mask(0b10));
+        (0..10).for_each(|_| space.step(FRAME));
+        assert!(space.get(ghost).unwrap().shape.top_left.y > 8.);
+        assert!((space.get(solid).unwrap().shape.top_left.y - 8.).abs() < 0.01);
+    }
+
+    #[test]
+    fn reports_contact_events() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        let a = space.insert(crate_at(0., 0.).velocity(glm::dvec2(100., 0.)));
+        let b = space.insert(Body {
+            kind: Kind::Static,
+            ..crate_at(2.5, 0.)
+        });
+        space.step(FRAME);
+        assert_eq!(space.events(), &[Event::Began(a, b)]);
+        space.get_mut(a).unwrap().velocity = glm::dvec2(100., 0.);
+        space.step(FRAME);
+        assert!(space.events().is_empty());
+        assert_eq!(space.contacts().len(), 1);
+
+        space.get_mut(a).unwrap().velocity = glm::dvec2(-100., 0.);
+        space.step(FRAME);
+        assert_eq!(space.events(), &[Event::Ended(a, b)]);
+    }
+
+    #[test]
+    fn reports_contacts_from_every_pass() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        let a = space.insert(crate_at(0., 0.));
+        let b = space.insert(crate_at(1.5, 0.));
+        let c = space.insert(crate_at(3.6, 0.));
+        space.step(FRAME);
+
+        let pairs: Vec<_> = space.contacts().iter().map(|c| (c.a, c.b)).collect();
+        assert_eq!(pairs, vec![(a, b), (b, c)]);
+        assert!(space.contacts()[0].depth >= 0.5);
+        assert_eq!(space.events(), &[Event::Began(a, b), Event::Began(b, c)]);
+    }
+
+    #[test]
+    fn steps_as_a_fixed_update_world() {
+        use crate::{
+            engine::step::{fixed, FixedUpdate, Runner, Snapshot, Step},
+            timer::GameTime,
+            Result,
+        };
+
+        struct Clock(Duration);
+
+        impl Runner<Space<Rectangle>, (), fixed::State> for Clock {
+            fn tick(&mut self, space: Space<Rectangle>, _: &GameTime) -> Space<Rectangle> {
+                space
+            }
+
+            fn update(
+                &mut self,
+                space: Space<Rectangle>,
+                elapsed: Duration,
+            ) -> State<Space<Rectangle>, ()> {
+                space.update(&input::State::default(), elapsed)
+            }
+
+            fn advance(&mut self, _: (), _: &Space<Rectangle>, _: &fixed::State) -> Result<()> {
+                Ok(())
+            }
+
+            fn time(&mut self) -> GameTime {
+                GameTime {
+                    total: self.0,
+                    since_update: self.0,
+                }
+            }
+        }
+
+        let mut space = Space::new(glm::dvec2(0., 10.));
+        let id = space.insert(crate_at(0., 0.));
+        let step = FixedUpdate::default().rate(100);
+        let snapshot = Snapshot::new::<FixedUpdate>(space, ());
+        let stepped = step.step(snapshot, &mut Clock(FRAME * 5 / 2));
+
+        let space = match stepped {
+            Ok(State::Running(snapshot)) => snapshot.world,
+            _ => panic!("expected the space to keep running"),
+        };
+        let body = space.get(id).unwrap();
+        assert!((body.velocity.y - 0.2).abs() < 0.00001);
+    }
+
+    #[test]
+    fn reuses_removed_ids() {
+        let mut space = Space::new(glm::dvec2(0., 0.));
+        let a = space.insert(crate_at(0., 0.));
+        space.insert(crate_at(5., 0.));
+        assert!(space.remove(a).is_some());
+        assert!(space.remove(a).is_none());
+        assert_eq!(space.len(), 1);
+        assert_eq!(space.insert(crate_at(0., 0.)), a);
+    }
+}
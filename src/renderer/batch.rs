@@ -0,0 +1,108 @@
+use super::{Draw, Options, Renderer};
+use crate::Result;
+
+/// Collects draws and submits consecutive draws of the same texture with a
+/// single `Draw::draw_all`, letting backends skip redundant texture and
+/// blend state changes between them. Draws are submitted in the order they
+/// were made, so overlapping sprites stay correctly layered.
+pub struct Batch<'a, T> {
+    runs: Vec<(&'a T, Vec<Options>)>,
+}
+
+impl<T> Default for Batch<'_, T> {
+    fn default() -> Self {
+        Batch { runs: vec![] }
+    }
+}
+
+impl<'a, T> Batch<'a, T> {
+    pub fn new() -> Self {
+        Batch::default()
+    }
+
+    pub fn draw(&mut self, texture: &'a T, options: Options) {
+        match self.runs.last_mut() {
+            Some((last, draws)) if std::ptr::eq(*last, texture) => draws.push(options),
+            _ => self.runs.push((texture, vec![options])),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(|(_, draws)| draws.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Number of `draw_all` calls the batch is submitted in
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Submits every draw, leaving the batch empty
+    pub fn flush<R: Renderer>(&mut self, renderer: &mut R) -> Result<()>
+    where
+        T: Draw<R>,
+    {
+        let drawn = self
+            .runs
+            .iter()
+            .try_for_each(|(texture, draws)| texture.draw_all(draws, renderer));
+        self.runs.clear();
+        drawn
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{align, mocks::MockCanvas, options},
+        texture::mocks::MockTexture,
+    };
+
+    #[test]
+    fn groups_adjacent_draws() {
+        let a = MockTexture {
+            dims: glm::uvec2(1, 1),
+        };
+        let b = MockTexture {
+            dims: glm::uvec2(2, 2),
+        };
+        let at = |x| options::at(align::left(x).top(0).dims(glm::uvec2(1, 1)));
+
+        let mut batch = Batch::new();
+        batch.draw(&a, at(0));
+        batch.draw(&a, at(1));
+        batch.draw(&b, at(2));
+        batch.draw(&a, at(3));
+        assert_eq!(batch.len(), 4);
+        assert_eq!(batch.runs(), 3);
+
+        let mut canvas = MockCanvas::new();
+        assert!(batch.flush(&mut canvas).is_ok());
+        assert!(batch.is_empty());
+        assert_eq!(
+            canvas.draw,
+            vec![(a, at(0)), (a, at(1)), (b, at(2)), (a, at(3))]
+        );
+    }
+
+    #[test]
+    fn groups_by_identity() {
+        let (a, b) = (
+            MockTexture {
+                dims: glm::uvec2(1, 1),
+            },
+            MockTexture {
+                dims: glm::uvec2(1, 1),
+            },
+        );
+
+        let mut batch = Batch::new();
+        batch.draw(&a, options::none());
+        batch.draw(&b, options::none());
+        assert_eq!(batch.runs(), 2);
+    }
+}
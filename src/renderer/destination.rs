@@ -94,6 +94,95 @@ impl Destination {
         let dims = self.dims * scale;
         Destination { dims, ..self }
     }
+
+    /// Overlapping area of both destinations, with no dimensions if they
+    /// do not overlap
+    pub fn intersection(&self, other: &Destination) -> Destination {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right()).max(left);
+        let bottom = self.bottom().min(other.bottom()).max(top);
+        let dims = glm::uvec2((right - left) as u32, (bottom - top) as u32);
+        align::left(left).top(top).dims(dims)
+    }
+
+    /// Scales by any factor, keeping the aligned edge or center in place
+    /// like `scale` does
+    pub fn scale_by(self, scale: f64) -> FDestination {
+        let dims = glm::to_dvec2(self.dims) * scale;
+        let horizontal = self.pos.horizontal;
+        let left = f64::from(horizontal.pos)
+            - match horizontal.align {
+                align::Horizontal::Left => 0.,
+                align::Horizontal::Center => dims.x / 2.,
+                align::Horizontal::Right => dims.x,
+            };
+        let vertical = self.pos.vertical;
+        let top = f64::from(vertical.pos)
+            - match vertical.align {
+                align::Vertical::Top => 0.,
+                align::Vertical::Middle => dims.y / 2.,
+                align::Vertical::Bottom => dims.y,
+            };
+        FDestination::new(glm::dvec2(left, top), dims)
+    }
+}
+
+/// Sub-pixel destination for things that move or scale smoothly; it is only
+/// rounded to whole pixels once it reaches the backend
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FDestination {
+    pub top_left: glm::DVec2,
+    pub dims: glm::DVec2,
+}
+
+impl FDestination {
+    pub fn new(top_left: glm::DVec2, dims: glm::DVec2) -> Self {
+        FDestination { top_left, dims }
+    }
+
+    pub fn center(&self) -> glm::DVec2 {
+        self.top_left + self.dims / 2.
+    }
+
+    pub fn bottom_right(&self) -> glm::DVec2 {
+        self.top_left + self.dims
+    }
+
+    pub fn nudge(self, nudge: glm::DVec2) -> Self {
+        let top_left = self.top_left + nudge;
+        FDestination { top_left, ..self }
+    }
+
+    pub fn scale(self, scale: f64) -> Self {
+        let dims = self.dims * scale;
+        FDestination { dims, ..self }
+    }
+
+    /// Rounds the edges rather than the size so that destinations which
+    /// touch keep touching once rounded
+    pub fn round(&self) -> Destination {
+        let top_left = glm::to_ivec2(glm::round(self.top_left));
+        let bottom_right = glm::to_ivec2(glm::round(self.bottom_right()));
+        let dims = glm::max(bottom_right - top_left, glm::ivec2(0, 0));
+        align::left(top_left.x)
+            .top(top_left.y)
+            .dims(glm::to_uvec2(dims))
+    }
+
+    /// Whether rounding lands on the same pixels as `dst`, whatever it is
+    /// aligned to
+    pub fn rounds_to(&self, dst: &Destination) -> bool {
+        let rounded = self.round();
+        (rounded.left(), rounded.top(), rounded.dims) == (dst.left(), dst.top(), dst.dims)
+    }
+}
+
+impl From<Destination> for FDestination {
+    fn from(dst: Destination) -> FDestination {
+        let top_left = glm::dvec2(f64::from(dst.left()), f64::from(dst.top()));
+        FDestination::new(top_left, glm::to_dvec2(dst.dims))
+    }
 }
 
 impl From<glm::IVec4> for Destination {
@@ -108,3 +197,48 @@ impl From<glm::IVec2> for Position {
         align::left(tl.x).top(tl.y)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intersects() {
+        let a = align::left(0).top(0).dims(glm::uvec2(10, 10));
+        let b = align::center(10).middle(5).dims(glm::uvec2(10, 4));
+        let c = align::left(20).top(0).dims(glm::uvec2(5, 5));
+        assert_eq!(
+            a.intersection(&b),
+            align::left(5).top(3).dims(glm::uvec2(5, 4))
+        );
+        assert_eq!(a.intersection(&c).dims, glm::uvec2(0, 5));
+    }
+
+    #[test]
+    fn rounds_edges() {
+        let left = FDestination::new(glm::dvec2(0.4, 1.6), glm::dvec2(10.3, 4.8));
+        let right = left.nudge(glm::dvec2(10.3, 0.));
+        assert_eq!(left.round(), align::left(0).top(2).dims(glm::uvec2(11, 4)));
+        assert_eq!(
+            right.round(),
+            align::left(11).top(2).dims(glm::uvec2(10, 4))
+        );
+    }
+
+    #[test]
+    fn scales_by_fraction() {
+        let dst = align::center(50).middle(50).dims(glm::uvec2(20, 10));
+        let scaled = dst.scale_by(1.5);
+        assert_eq!(scaled.top_left, glm::dvec2(35., 42.5));
+        assert_eq!(scaled.dims, glm::dvec2(30., 15.));
+        assert_eq!(
+            scaled.round(),
+            align::left(35).top(43).dims(glm::uvec2(30, 15))
+        );
+
+        let dst = align::left(10).bottom(50).dims(glm::uvec2(20, 10));
+        let scaled = dst.scale_by(0.5);
+        assert_eq!(scaled.top_left, glm::dvec2(10., 45.));
+        assert_eq!(scaled.dims, glm::dvec2(10., 5.));
+    }
+}
@@ -1,18 +1,27 @@
 pub mod align;
 pub mod options;
 
+mod batch;
 mod destination;
+mod primitives;
+mod queue;
+mod resolution;
+mod transform;
 
 pub use self::{
-    destination::{Destination, Position},
+    batch::Batch,
+    destination::{Destination, FDestination, Position},
     options::Options,
+    queue::{Depth, Queue},
+    resolution::{Resolution, Scaling, Viewport},
+    transform::{Transform, Transformed},
 };
 
 use crate::Result;
 
 use std::rc::Rc;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ColorRGBA(pub u8, pub u8, pub u8, pub u8);
 
 pub trait Window {
@@ -33,12 +42,20 @@ impl<R: Renderer, T: Show<R>> Show<R> for Rc<T> {
 
 impl<R: Renderer, T: Show<R>> Show<R> for Vec<T> {
     fn show(&self, renderer: &mut R) -> Result<()> {
-        self.iter().map(|t| renderer.show(t)).collect()
+        self.iter().try_for_each(|t| renderer.show(t))
     }
 }
 
 pub trait Draw<R: ?Sized>: Show<R> {
     fn draw(&self, options: Options, renderer: &mut R) -> Result<()>;
+
+    /// Draws once per options; backends override this to share the setup
+    /// of consecutive copies
+    fn draw_all(&self, options: &[Options], renderer: &mut R) -> Result<()> {
+        options
+            .iter()
+            .try_for_each(|o| self.draw(o.clone(), renderer))
+    }
 }
 
 pub trait Show<R: ?Sized> {
@@ -50,11 +67,106 @@ pub trait Canvas: Renderer {
     fn present(&mut self);
 }
 
+/// Canvases that can draw into a texture instead of their usual target
+pub trait Target<T>: Canvas + Sized {
+    /// Clears `texture` to transparent before showing `scene` in it
+    fn render_to(&mut self, texture: &mut T, scene: &impl Show<Self>) -> Result<()>;
+}
+
+/// Clip rectangle that was active before a `push_clip`, to be handed back to
+/// `pop_clip`; `None` when nothing was being clipped
+#[must_use]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Clip(pub Option<Destination>);
+
 pub trait Renderer {
     fn set_draw_color(&mut self, color: ColorRGBA);
     fn fill_rects(&mut self, rects: &[Destination]) -> Result<()>;
     fn draw_rects(&mut self, rects: &[Destination]) -> Result<()>;
 
+    /// Draws connected lines through every point; the default draws every
+    /// pixel of them as a point
+    fn draw_lines(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let pixels: Vec<_> = points
+            .windows(2)
+            .flat_map(|segment| primitives::line(segment[0], segment[1]))
+            .collect();
+        self.draw_points(&pixels)
+    }
+
+    /// Default implementation fills a single pixel rectangle per point
+    fn draw_points(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let rects: Vec<_> = points
+            .iter()
+            .map(|p| align::left(p.x).top(p.y).dims(glm::uvec2(1, 1)))
+            .collect();
+        self.fill_rects(&rects)
+    }
+
+    /// Restricts drawing to the part of `rect` inside of the current clip;
+    /// renderers that cannot clip keep drawing everything
+    fn push_clip(&mut self, _rect: Destination) -> Clip {
+        Clip(None)
+    }
+
+    fn pop_clip(&mut self, _clip: Clip) {}
+
+    /// Depth that the following draws are sorted at by renderers that sort
+    /// them, like `Queue`, returning the depth it replaces; everything else
+    /// draws right away and ignores it
+    fn set_depth(&mut self, _depth: Depth) -> Depth {
+        Depth::default()
+    }
+
+    fn at_depth(
+        &mut self,
+        depth: impl Into<Depth>,
+        draw: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let previous = self.set_depth(depth.into());
+        let drawn = draw(self);
+        self.set_depth(previous);
+        drawn
+    }
+
+    fn clipped(
+        &mut self,
+        rect: Destination,
+        draw: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        let clip = self.push_clip(rect);
+        let drawn = draw(self);
+        self.pop_clip(clip);
+        drawn
+    }
+
+    fn draw_ellipse(&mut self, center: glm::IVec2, radii: glm::UVec2) -> Result<()> {
+        self.draw_lines(&primitives::ellipse(center, radii))
+    }
+
+    fn fill_ellipse(&mut self, center: glm::IVec2, radii: glm::UVec2) -> Result<()> {
+        self.fill_polygon(&primitives::ellipse(center, radii))
+    }
+
+    fn draw_circle(&mut self, center: glm::IVec2, radius: u32) -> Result<()> {
+        self.draw_ellipse(center, glm::uvec2(radius, radius))
+    }
+
+    fn fill_circle(&mut self, center: glm::IVec2, radius: u32) -> Result<()> {
+        self.fill_ellipse(center, glm::uvec2(radius, radius))
+    }
+
+    /// Only convex polygons are filled correctly
+    fn fill_polygon(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        self.fill_rects(&primitives::spans(points))
+    }
+
     /// Default implemenations for drawing assets
     fn draw(&mut self, asset: &impl Draw<Self>, options: Options) -> Result<()> {
         asset.draw(options, self)
@@ -74,19 +186,63 @@ pub mod mocks {
     #[derive(Default)]
     pub struct MockCanvas {
         pub draw: Vec<(MockTexture, Options)>,
+        pub color: Option<ColorRGBA>,
+        pub fill: Vec<Destination>,
+        pub outline: Vec<Destination>,
+        pub lines: Vec<Vec<glm::IVec2>>,
+        pub points: Vec<glm::IVec2>,
+        pub targets: Vec<(MockTexture, MockCanvas)>,
+        pub clip: Option<Destination>,
     }
 
     impl super::Renderer for MockCanvas {
-        fn set_draw_color(&mut self, _: ColorRGBA) {
-            unimplemented!()
+        fn set_draw_color(&mut self, color: ColorRGBA) {
+            self.color = Some(color);
         }
 
-        fn fill_rects(&mut self, _: &[Destination]) -> Result<()> {
-            unimplemented!()
+        fn fill_rects(&mut self, rects: &[Destination]) -> Result<()> {
+            let clipped = rects.iter().filter_map(|r| self.clipped_rect(*r));
+            self.fill.extend(clipped.collect::<Vec<_>>());
+            Ok(())
         }
 
-        fn draw_rects(&mut self, _: &[Destination]) -> Result<()> {
-            unimplemented!()
+        fn draw_rects(&mut self, rects: &[Destination]) -> Result<()> {
+            let clipped = rects.iter().filter_map(|r| self.clipped_rect(*r));
+            self.outline.extend(clipped.collect::<Vec<_>>());
+            Ok(())
+        }
+
+        /// Clipped lines are kept as the separate segments left inside
+        fn draw_lines(&mut self, points: &[glm::IVec2]) -> Result<()> {
+            let clip = match self.clip {
+                Some(clip) => clip,
+                None => {
+                    self.lines.push(points.to_vec());
+                    return Ok(());
+                }
+            };
+            let segments = points
+                .windows(2)
+                .filter_map(|s| clip_segment(s[0], s[1], clip))
+                .map(|(a, b)| vec![a, b]);
+            self.lines.extend(segments.collect::<Vec<_>>());
+            Ok(())
+        }
+
+        fn draw_points(&mut self, points: &[glm::IVec2]) -> Result<()> {
+            let clipped = points.iter().filter(|p| self.contains(**p));
+            self.points.extend(clipped.collect::<Vec<_>>());
+            Ok(())
+        }
+
+        fn push_clip(&mut self, rect: Destination) -> Clip {
+            let previous = self.clip;
+            self.clip = Some(previous.map_or(rect, |p| p.intersection(&rect)));
+            Clip(previous)
+        }
+
+        fn pop_clip(&mut self, clip: Clip) {
+            self.clip = clip.0;
         }
     }
 
@@ -95,9 +251,144 @@ pub mod mocks {
         fn present(&mut self) {}
     }
 
+    impl super::Target<MockTexture> for MockCanvas {
+        fn render_to(&mut self, texture: &mut MockTexture, scene: &impl Show<Self>) -> Result<()> {
+            let mut target = MockCanvas::new();
+            scene.show(&mut target)?;
+            self.targets.push((*texture, target));
+            Ok(())
+        }
+    }
+
     impl MockCanvas {
         pub fn new() -> Self {
             MockCanvas::default()
         }
+
+        /// Part of `rect` left after clipping, if any
+        pub fn clipped_rect(&self, rect: Destination) -> Option<Destination> {
+            let rect = self.clip.map_or(rect, |c| c.intersection(&rect));
+            if rect.dims.x > 0 && rect.dims.y > 0 {
+                Some(rect)
+            } else {
+                None
+            }
+        }
+
+        fn contains(&self, point: glm::IVec2) -> bool {
+            self.clip.map_or(true, |c| inside(point, c))
+        }
+    }
+
+    fn inside(point: glm::IVec2, rect: Destination) -> bool {
+        (rect.left()..rect.right()).contains(&point.x)
+            && (rect.top()..rect.bottom()).contains(&point.y)
+    }
+
+    /// Part of the segment inside `clip`, rounded to whole pixels
+    fn clip_segment(
+        a: glm::IVec2,
+        b: glm::IVec2,
+        clip: Destination,
+    ) -> Option<(glm::IVec2, glm::IVec2)> {
+        let (start, delta) = (glm::to_dvec2(a), glm::to_dvec2(b - a));
+        let min = glm::dvec2(f64::from(clip.left()), f64::from(clip.top()));
+        let max = glm::dvec2(f64::from(clip.right() - 1), f64::from(clip.bottom() - 1));
+        let (mut enter, mut exit) = (0_f64, 1_f64);
+        for axis in 0..2 {
+            if delta[axis] == 0. {
+                if start[axis] < min[axis] || start[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let near = (min[axis] - start[axis]) / delta[axis];
+            let far = (max[axis] - start[axis]) / delta[axis];
+            enter = enter.max(near.min(far));
+            exit = exit.min(near.max(far));
+        }
+        if enter > exit {
+            return None;
+        }
+        let at = |t: f64| glm::to_ivec2(glm::round(start + delta * t));
+        Some((at(enter), at(exit)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{mocks::MockCanvas, *};
+
+    #[derive(Default)]
+    struct PixelRenderer {
+        fill: Vec<Destination>,
+    }
+
+    impl Renderer for PixelRenderer {
+        fn set_draw_color(&mut self, _: ColorRGBA) {}
+
+        fn fill_rects(&mut self, rects: &[Destination]) -> Result<()> {
+            self.fill.extend_from_slice(rects);
+            Ok(())
+        }
+
+        fn draw_rects(&mut self, _: &[Destination]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn pixel(x: i32, y: i32) -> Destination {
+        align::left(x).top(y).dims(glm::uvec2(1, 1))
+    }
+
+    #[test]
+    fn draws_lines_and_points_as_pixels_by_default() {
+        let mut renderer = PixelRenderer::default();
+        let lines = [glm::ivec2(0, 0), glm::ivec2(2, 0), glm::ivec2(2, 1)];
+        assert!(renderer.draw_lines(&lines).is_ok());
+        assert!(renderer.draw_points(&[glm::ivec2(5, 5)]).is_ok());
+        let clipped = renderer.clipped(pixel(0, 0), |r| r.draw_points(&[glm::ivec2(9, 9)]));
+        assert!(clipped.is_ok());
+
+        let expected = vec![
+            pixel(0, 0),
+            pixel(1, 0),
+            pixel(2, 0),
+            pixel(2, 0),
+            pixel(2, 1),
+            pixel(5, 5),
+            pixel(9, 9),
+        ];
+        assert_eq!(renderer.fill, expected);
+    }
+
+    #[test]
+    fn clips_primitives() {
+        let mut canvas = MockCanvas::new();
+        let clip = align::left(0).top(0).dims(glm::uvec2(10, 10));
+        let drawn = canvas.clipped(clip, |c| {
+            let across = align::left(5).top(5).dims(glm::uvec2(10, 10));
+            let outside = align::left(20).top(0).dims(glm::uvec2(5, 5));
+            c.draw_rects(&[across, outside])?;
+            c.draw_points(&[glm::ivec2(9, 9), glm::ivec2(10, 9), glm::ivec2(-1, 0)])?;
+            c.draw_lines(&[
+                glm::ivec2(-5, 5),
+                glm::ivec2(15, 5),
+                glm::ivec2(15, 20),
+                glm::ivec2(5, 5),
+            ])
+        });
+        assert!(drawn.is_ok());
+
+        let clipped = align::left(5).top(5).dims(glm::uvec2(5, 5));
+        assert_eq!(canvas.outline, vec![clipped]);
+        assert_eq!(canvas.points, vec![glm::ivec2(9, 9)]);
+        assert_eq!(
+            canvas.lines,
+            vec![
+                vec![glm::ivec2(0, 5), glm::ivec2(9, 5)],
+                vec![glm::ivec2(8, 9), glm::ivec2(5, 5)],
+            ]
+        );
     }
 }
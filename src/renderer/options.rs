@@ -1,4 +1,4 @@
-use super::Destination;
+use super::{ColorRGBA, Destination, FDestination};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rotation {
@@ -13,20 +13,48 @@ pub enum Flip {
     Both,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blend {
+    None,
+    Alpha,
+    Add,
+    Mod,
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Options {
     pub dst: Option<Destination>,
+    /// Sub-pixel destination that `dst` was rounded from, so that transforms
+    /// apply before rounding; it takes precedence over `dst` only while it
+    /// still rounds to it, so changing `dst` alone draws at the new `dst`
+    pub fdst: Option<FDestination>,
     pub src: Option<glm::UVec4>,
     pub rotation: Option<Rotation>,
     pub flip: Option<Flip>,
+    /// Multiplies the texture's colors, its alpha channel fading it out
+    pub color: Option<ColorRGBA>,
+    pub blend: Option<Blend>,
 }
 
 impl Options {
     pub fn at(mut self, dst: impl Into<Destination>) -> Self {
         self.dst = Some(dst.into());
+        self.fdst = None;
         self
     }
 
+    pub fn at_precise(mut self, dst: FDestination) -> Self {
+        self.dst = Some(dst.round());
+        self.fdst = Some(dst);
+        self
+    }
+
+    /// `fdst` when it takes precedence over `dst`
+    pub fn precise_dst(&self) -> Option<FDestination> {
+        let dst = self.dst?;
+        self.fdst.filter(|fdst| fdst.rounds_to(&dst))
+    }
+
     pub fn from(mut self, src: glm::UVec4) -> Self {
         self.src = Some(src);
         self
@@ -41,6 +69,22 @@ impl Options {
         self.rotation = Some(rotation);
         self
     }
+
+    pub fn color(mut self, color: ColorRGBA) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn alpha(mut self, alpha: u8) -> Self {
+        let ColorRGBA(r, g, b, _) = self.color.unwrap_or(ColorRGBA(255, 255, 255, 255));
+        self.color = Some(ColorRGBA(r, g, b, alpha));
+        self
+    }
+
+    pub fn blend(mut self, blend: Blend) -> Self {
+        self.blend = Some(blend);
+        self
+    }
 }
 
 pub fn none() -> Options {
@@ -51,6 +95,10 @@ pub fn at(dst: impl Into<Destination>) -> Options {
     Options::default().at(dst)
 }
 
+pub fn at_precise(dst: FDestination) -> Options {
+    Options::default().at_precise(dst)
+}
+
 pub fn from(src: glm::UVec4) -> Options {
     Options::default().from(src)
 }
@@ -62,3 +110,15 @@ pub fn flip(flip: Flip) -> Options {
 pub fn rotate(rotation: Rotation) -> Options {
     Options::default().rotate(rotation)
 }
+
+pub fn color(color: ColorRGBA) -> Options {
+    Options::default().color(color)
+}
+
+pub fn alpha(alpha: u8) -> Options {
+    Options::default().alpha(alpha)
+}
+
+pub fn blend(blend: Blend) -> Options {
+    Options::default().blend(blend)
+}
@@ -0,0 +1,123 @@
+use super::{align, Destination};
+
+use std::f64::consts::PI;
+
+/// Closed outline of an ellipse, with the first point repeated at the end
+pub fn ellipse(center: glm::IVec2, radii: glm::UVec2) -> Vec<glm::IVec2> {
+    let radii = glm::to_dvec2(radii);
+    let perimeter = PI * (radii.x + radii.y);
+    let segments = ((perimeter / 4.).ceil() as usize).clamp(8, 360);
+    let center = glm::to_dvec2(center);
+    (0..=segments)
+        .map(|i| {
+            let angle = 2. * PI * i as f64 / segments as f64;
+            let (sin, cos) = angle.sin_cos();
+            glm::to_ivec2(glm::round(center + glm::dvec2(cos, sin) * radii))
+        })
+        .collect()
+}
+
+/// Every pixel on the line from `from` to `to`, both ends included
+pub fn line(from: glm::IVec2, to: glm::IVec2) -> Vec<glm::IVec2> {
+    let delta = to - from;
+    let steps = delta.x.abs().max(delta.y.abs());
+    if steps == 0 {
+        return vec![from];
+    }
+    let (from, delta) = (glm::to_dvec2(from), glm::to_dvec2(delta));
+    (0..=steps)
+        .map(|i| {
+            let point = from + delta * (f64::from(i) / f64::from(steps));
+            glm::to_ivec2(glm::round(point))
+        })
+        .collect()
+}
+
+/// One pixel tall rectangles covering a convex polygon, sampled at the
+/// middle of each row
+pub fn spans(polygon: &[glm::IVec2]) -> Vec<Destination> {
+    let (top, bottom) = match (
+        polygon.iter().map(|p| p.y).min(),
+        polygon.iter().map(|p| p.y).max(),
+    ) {
+        (Some(top), Some(bottom)) => (top, bottom),
+        _ => return vec![],
+    };
+    let edges = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .filter(|(a, b)| a.y != b.y);
+
+    (top..bottom)
+        .filter_map(|y| {
+            let row = f64::from(y) + 0.5;
+            let crossings = edges.clone().filter_map(|(a, b)| {
+                let (a, b) = (glm::to_dvec2(*a), glm::to_dvec2(*b));
+                if row < a.y.min(b.y) || row > a.y.max(b.y) {
+                    return None;
+                }
+                Some(a.x + (row - a.y) * (b.x - a.x) / (b.y - a.y))
+            });
+            let (left, right) =
+                crossings.fold((f64::MAX, f64::MIN), |(l, r), x| (l.min(x), r.max(x)));
+            let (left, right) = (left.round() as i32, right.round() as i32);
+            if right > left {
+                let dims = glm::uvec2((right - left) as u32, 1);
+                Some(align::left(left).top(y).dims(dims))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn closes_ellipse() {
+        let points = ellipse(glm::ivec2(10, 20), glm::uvec2(6, 3));
+        assert!(points.len() > 8);
+        assert_eq!(points.first(), points.last());
+        assert_eq!(points[0], glm::ivec2(16, 20));
+        assert!(points
+            .iter()
+            .all(|p| (4..=16).contains(&p.x) && (17..=23).contains(&p.y)));
+    }
+
+    #[test]
+    fn rasterizes_lines() {
+        let points = line(glm::ivec2(0, 0), glm::ivec2(4, -2));
+        assert_eq!(
+            points,
+            vec![
+                glm::ivec2(0, 0),
+                glm::ivec2(1, -1),
+                glm::ivec2(2, -1),
+                glm::ivec2(3, -2),
+                glm::ivec2(4, -2),
+            ]
+        );
+        assert_eq!(
+            line(glm::ivec2(3, 3), glm::ivec2(3, 3)),
+            vec![glm::ivec2(3, 3)]
+        );
+    }
+
+    #[test]
+    fn spans_triangle() {
+        let triangle = [glm::ivec2(0, 0), glm::ivec2(4, 4), glm::ivec2(0, 4)];
+        let expected: Vec<_> = (0..4)
+            .map(|y| align::left(0).top(y).dims(glm::uvec2(y as u32 + 1, 1)))
+            .collect();
+        assert_eq!(spans(&triangle), expected);
+    }
+
+    #[test]
+    fn spans_nothing_when_flat() {
+        let line = [glm::ivec2(0, 2), glm::ivec2(6, 2)];
+        assert_eq!(spans(&line), vec![]);
+        assert_eq!(spans(&[]), vec![]);
+    }
+}
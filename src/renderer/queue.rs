@@ -0,0 +1,324 @@
+use super::{Canvas, Clip, ColorRGBA, Destination, Renderer, Show, Window};
+use crate::Result;
+
+/// Where a queued draw lands: lower layers are drawn first and, within a
+/// layer, lower `y` values are drawn first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Depth {
+    pub layer: i32,
+    pub y: i32,
+}
+
+impl Depth {
+    pub fn layer(layer: i32) -> Self {
+        Depth { layer, y: 0 }
+    }
+
+    /// Sorts by the bottom edge of `dst` so that things lower on the
+    /// screen are drawn in front, as top-down games expect
+    pub fn y_sorted(layer: i32, dst: Destination) -> Self {
+        Depth {
+            layer,
+            y: dst.bottom(),
+        }
+    }
+}
+
+impl From<i32> for Depth {
+    fn from(layer: i32) -> Self {
+        Depth::layer(layer)
+    }
+}
+
+type DrawCommand<'t, R> = Box<dyn FnOnce(&mut R) -> Result<()> + 't>;
+
+struct Command<'t, R> {
+    depth: Depth,
+    clip: Option<Destination>,
+    draw: DrawCommand<'t, R>,
+}
+
+/// Renderer that holds on to everything drawn through it, at the depth set
+/// by `Renderer::at_depth`, and draws it sorted by `Depth` when the frame is
+/// presented; draws that share a depth keep the order they were made in.
+///
+/// Draws are kept past the `show` that made them, so textures are drawn
+/// through a queue by sharing them in an `Rc` rather than by reference.
+pub struct Queue<'t, R> {
+    renderer: R,
+    commands: Vec<Command<'t, R>>,
+    depth: Depth,
+    color: Option<ColorRGBA>,
+    clip: Option<Destination>,
+    failed: Option<failure::Error>,
+}
+
+impl<'t, R: Renderer> Queue<'t, R> {
+    pub fn new(renderer: R) -> Self {
+        Queue {
+            renderer,
+            commands: vec![],
+            depth: Depth::default(),
+            color: None,
+            clip: None,
+            failed: None,
+        }
+    }
+
+    /// Queues `draw` to run against the wrapped renderer at the current
+    /// depth; this is how assets implement `Draw` for a queue
+    pub fn push(&mut self, draw: impl FnOnce(&mut R) -> Result<()> + 't) {
+        self.commands.push(Command {
+            depth: self.depth,
+            clip: self.clip,
+            draw: Box::new(draw),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Draws everything queued so far, leaving the queue empty
+    pub fn flush(&mut self) -> Result<()> {
+        self.commands.sort_by_key(|c| c.depth);
+        let renderer = &mut self.renderer;
+        let mut applied = None;
+        let mut previous = Clip(None);
+        let drawn = self.commands.drain(..).try_for_each(|command| {
+            if command.clip != applied {
+                if applied.is_some() {
+                    renderer.pop_clip(previous);
+                }
+                if let Some(clip) = command.clip {
+                    previous = renderer.push_clip(clip);
+                }
+                applied = command.clip;
+            }
+            (command.draw)(renderer)
+        });
+        if applied.is_some() {
+            renderer.pop_clip(previous);
+        }
+        drawn
+    }
+
+    /// Drops anything still queued
+    pub fn into_inner(self) -> R {
+        self.renderer
+    }
+
+    fn primitive(&mut self, draw: impl FnOnce(&mut R) -> Result<()> + 't) {
+        let color = self.color;
+        self.push(move |renderer| {
+            if let Some(color) = color {
+                renderer.set_draw_color(color);
+            }
+            draw(renderer)
+        });
+    }
+}
+
+impl<'t, R: Renderer> Renderer for Queue<'t, R> {
+    fn set_draw_color(&mut self, color: ColorRGBA) {
+        self.color = Some(color);
+    }
+
+    fn fill_rects(&mut self, rects: &[Destination]) -> Result<()> {
+        let rects = rects.to_vec();
+        self.primitive(move |renderer| renderer.fill_rects(&rects));
+        Ok(())
+    }
+
+    fn draw_rects(&mut self, rects: &[Destination]) -> Result<()> {
+        let rects = rects.to_vec();
+        self.primitive(move |renderer| renderer.draw_rects(&rects));
+        Ok(())
+    }
+
+    fn draw_lines(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points = points.to_vec();
+        self.primitive(move |renderer| renderer.draw_lines(&points));
+        Ok(())
+    }
+
+    fn draw_points(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points = points.to_vec();
+        self.primitive(move |renderer| renderer.draw_points(&points));
+        Ok(())
+    }
+
+    /// Queued draws remember the clip they were made with
+    fn push_clip(&mut self, rect: Destination) -> Clip {
+        let previous = self.clip;
+        self.clip = Some(previous.map_or(rect, |p| p.intersection(&rect)));
+        Clip(previous)
+    }
+
+    fn pop_clip(&mut self, clip: Clip) {
+        self.clip = clip.0;
+    }
+
+    fn set_depth(&mut self, depth: Depth) -> Depth {
+        std::mem::replace(&mut self.depth, depth)
+    }
+
+    /// Also reports a failure to draw the previously presented frame
+    fn show(&mut self, scene: &impl Show<Self>) -> Result<()> {
+        match self.failed.take() {
+            Some(error) => Err(error),
+            None => scene.show(self),
+        }
+    }
+}
+
+impl<R: Canvas> Canvas for Queue<'_, R> {
+    fn clear(&mut self) {
+        self.commands.clear();
+        self.renderer.clear();
+    }
+
+    /// Draws everything queued first; since presenting cannot fail, a
+    /// failed draw is returned by the next `show` instead
+    fn present(&mut self) {
+        if let Err(error) = self.flush() {
+            self.failed.get_or_insert(error);
+        }
+        self.renderer.present();
+    }
+}
+
+impl<R: Window> Window for Queue<'_, R> {
+    fn output_size(&self) -> Result<glm::UVec2> {
+        self.renderer.output_size()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{align, mocks::MockCanvas, options, Draw},
+        texture::{mocks::MockTexture, Texture},
+    };
+
+    fn texture(width: u32) -> MockTexture {
+        MockTexture {
+            dims: glm::uvec2(width, 1),
+        }
+    }
+
+    fn drawn(queue: Queue<'_, MockCanvas>) -> Vec<MockTexture> {
+        queue.into_inner().draw.iter().map(|(t, _)| *t).collect()
+    }
+
+    struct Scene {
+        front: MockTexture,
+        middle: MockTexture,
+        back: MockTexture,
+    }
+
+    impl<R: Renderer> Show<R> for Scene
+    where
+        MockTexture: Draw<R>,
+    {
+        fn show(&self, renderer: &mut R) -> Result<()> {
+            let front = self.front.at(align::left(0).top(0));
+            renderer.at_depth(2, |r| r.show(&front))?;
+            renderer.draw(&self.middle, options::none())?;
+            renderer.at_depth(-1, |r| r.draw(&self.back, options::none()))
+        }
+    }
+
+    #[test]
+    fn draws_by_layer_when_presented() {
+        let scene = Scene {
+            front: texture(1),
+            middle: texture(2),
+            back: texture(3),
+        };
+
+        let mut queue = Queue::new(MockCanvas::new());
+        assert!(queue.show(&scene).is_ok());
+        assert_eq!(queue.len(), 3);
+        assert!(queue.renderer.draw.is_empty());
+
+        queue.present();
+        assert!(queue.is_empty());
+        assert_eq!(drawn(queue), vec![scene.back, scene.middle, scene.front]);
+    }
+
+    #[test]
+    fn draws_immediately_without_a_queue() {
+        let scene = Scene {
+            front: texture(1),
+            middle: texture(2),
+            back: texture(3),
+        };
+
+        let mut canvas = MockCanvas::new();
+        assert!(canvas.show(&scene).is_ok());
+        let drawn: Vec<_> = canvas.draw.iter().map(|(t, _)| *t).collect();
+        assert_eq!(drawn, vec![scene.front, scene.middle, scene.back]);
+    }
+
+    #[test]
+    fn keeps_drawn_order_within_depth() {
+        let textures: Vec<_> = (1..5).map(texture).collect();
+
+        let mut queue = Queue::new(MockCanvas::new());
+        for texture in &textures {
+            assert!(queue.draw(texture, options::none()).is_ok());
+        }
+        assert!(queue.flush().is_ok());
+        assert_eq!(drawn(queue), textures);
+    }
+
+    #[test]
+    fn y_sorts_within_layer() {
+        let (tall, short, overlay) = (texture(1), texture(2), texture(3));
+        let tall_dst = align::left(0).bottom(40).dims(glm::uvec2(5, 30));
+        let short_dst = align::left(0).top(20).dims(glm::uvec2(5, 5));
+
+        let mut queue = Queue::new(MockCanvas::new());
+        let overlaid = queue.at_depth(1, |q| q.draw(&overlay, options::none()));
+        assert!(overlaid.is_ok());
+        let tall_drawn = queue.at_depth(Depth::y_sorted(0, tall_dst), |q| {
+            q.draw(&tall, options::at(tall_dst))
+        });
+        assert!(tall_drawn.is_ok());
+        let short_drawn = queue.at_depth(Depth::y_sorted(0, short_dst), |q| {
+            q.draw(&short, options::at(short_dst))
+        });
+        assert!(short_drawn.is_ok());
+
+        queue.present();
+        assert_eq!(drawn(queue), vec![short, tall, overlay]);
+    }
+
+    #[test]
+    fn keeps_colors_and_clips() {
+        let clip = align::left(0).top(0).dims(glm::uvec2(10, 10));
+        let inside = align::left(2).top(2).dims(glm::uvec2(4, 4));
+        let across = align::left(5).top(5).dims(glm::uvec2(10, 10));
+
+        let mut queue = Queue::new(MockCanvas::new());
+        let drawn = queue.at_depth(1, |q| {
+            q.set_draw_color(ColorRGBA(255, 0, 0, 255));
+            q.clipped(clip, |q| q.fill_rects(&[across]))
+        });
+        assert!(drawn.is_ok());
+        assert!(queue.fill_rects(&[inside]).is_ok());
+        assert!(queue.flush().is_ok());
+
+        let canvas = queue.into_inner();
+        let clipped = align::left(5).top(5).dims(glm::uvec2(5, 5));
+        assert_eq!(canvas.fill, vec![inside, clipped]);
+        assert_eq!(canvas.color, Some(ColorRGBA(255, 0, 0, 255)));
+        assert_eq!(canvas.clip, None);
+    }
+}
@@ -0,0 +1,228 @@
+use super::{
+    options::Rotation, ColorRGBA, Destination, FDestination, Renderer, Transform, Transformed,
+    Window,
+};
+use crate::{input, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scaling {
+    /// Largest whole multiple that fits, keeping pixels square and sharp
+    #[default]
+    Integer,
+    /// Largest size that fits while keeping the aspect ratio
+    Fit,
+    /// Fills the whole output, distorting the aspect ratio if needed
+    Stretch,
+}
+
+/// Fixed logical resolution that scenes are drawn in regardless of the
+/// size of the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub logical: glm::UVec2,
+    pub scaling: Scaling,
+}
+
+impl Resolution {
+    pub fn new(logical: glm::UVec2) -> Self {
+        Resolution {
+            logical,
+            scaling: Scaling::default(),
+        }
+    }
+
+    pub fn scaling(mut self, scaling: Scaling) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    pub fn viewport(&self, output: glm::UVec2) -> Viewport {
+        let logical = glm::to_dvec2(self.logical);
+        let ratio = glm::to_dvec2(output) / logical;
+        let fit = ratio.x.min(ratio.y);
+        let scale = match self.scaling {
+            Scaling::Stretch => ratio,
+            Scaling::Fit => glm::dvec2(fit, fit),
+            Scaling::Integer if fit >= 1. => glm::dvec2(fit.floor(), fit.floor()),
+            Scaling::Integer => glm::dvec2(fit, fit),
+        };
+        let offset = glm::floor((glm::to_dvec2(output) - logical * scale) / 2.);
+        Viewport {
+            logical: self.logical,
+            output,
+            scale,
+            offset,
+        }
+    }
+
+    /// Viewport for the current size of `window`
+    pub fn fit(&self, window: &impl Window) -> Result<Viewport> {
+        Ok(self.viewport(window.output_size()?))
+    }
+}
+
+/// Placement of a `Resolution` within an output of a particular size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub logical: glm::UVec2,
+    pub output: glm::UVec2,
+    pub scale: glm::DVec2,
+    pub offset: glm::DVec2,
+}
+
+impl Viewport {
+    /// Wraps a renderer so that destinations are given in logical pixels
+    pub fn renderer<'a, R>(&self, renderer: &'a mut R) -> Transformed<'a, R, Viewport> {
+        Transformed::new(renderer, *self)
+    }
+
+    /// Output area the logical resolution is scaled into
+    pub fn area(&self) -> Destination {
+        let dims = glm::to_dvec2(self.logical) * self.scale;
+        FDestination::new(self.offset, dims).round()
+    }
+
+    /// Areas of the output left uncovered on either side of the viewport
+    pub fn bars(&self) -> Vec<Destination> {
+        let area = self.area();
+        let output = glm::to_ivec2(self.output);
+        let rects = [
+            glm::ivec4(0, 0, output.x, area.top()),
+            glm::ivec4(0, area.bottom(), output.x, output.y - area.bottom()),
+            glm::ivec4(0, area.top(), area.left(), area.dims.y as i32),
+            glm::ivec4(
+                area.right(),
+                area.top(),
+                output.x - area.right(),
+                area.dims.y as i32,
+            ),
+        ];
+        rects
+            .iter()
+            .filter(|r| r.z > 0 && r.w > 0)
+            .map(|&r| r.into())
+            .collect()
+    }
+
+    /// Covers the bars in `color`; done after drawing the scene so that
+    /// anything spilling outside of the logical area is hidden
+    pub fn letterbox(&self, renderer: &mut impl Renderer, color: ColorRGBA) -> Result<()> {
+        let bars = self.bars();
+        if bars.is_empty() {
+            return Ok(());
+        }
+        renderer.set_draw_color(color);
+        renderer.fill_rects(&bars)
+    }
+
+    /// Converts output coordinates into logical ones; these fall outside of
+    /// the logical resolution when over the bars
+    pub fn to_logical(&self, coords: glm::IVec2) -> glm::IVec2 {
+        let logical = (glm::to_dvec2(coords) - self.offset) / self.scale;
+        glm::to_ivec2(glm::floor(logical))
+    }
+
+    pub fn mouse_coords(&self, input: &input::State) -> glm::IVec2 {
+        self.to_logical(input.mouse_coords())
+    }
+}
+
+impl Transform for Viewport {
+    fn destination(&self, dst: Destination) -> Destination {
+        self.precise(dst.into()).round()
+    }
+
+    fn precise(&self, dst: FDestination) -> FDestination {
+        FDestination::new(
+            dst.top_left * self.scale + self.offset,
+            dst.dims * self.scale,
+        )
+    }
+
+    fn rotation(&self, rotation: Rotation) -> Rotation {
+        let center = glm::to_dvec2(rotation.center) * self.scale;
+        Rotation {
+            center: glm::to_ivec2(glm::round(center)),
+            ..rotation
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{align, mocks::MockCanvas, options},
+        texture::mocks::MockTexture,
+    };
+
+    #[test]
+    fn integer_scaling() {
+        let resolution = Resolution::new(glm::uvec2(320, 180));
+        let viewport = resolution.viewport(glm::uvec2(1000, 600));
+        assert_eq!(viewport.scale, glm::dvec2(3., 3.));
+        assert_eq!(viewport.offset, glm::dvec2(20., 30.));
+        assert_eq!(
+            viewport.area(),
+            align::left(20).top(30).dims(glm::uvec2(960, 540))
+        );
+    }
+
+    #[test]
+    fn fit_scaling() {
+        let resolution = Resolution::new(glm::uvec2(320, 180)).scaling(Scaling::Fit);
+        let viewport = resolution.viewport(glm::uvec2(800, 600));
+        assert_eq!(viewport.scale, glm::dvec2(2.5, 2.5));
+        assert_eq!(viewport.offset, glm::dvec2(0., 75.));
+        assert_eq!(
+            viewport.bars(),
+            vec![
+                align::left(0).top(0).dims(glm::uvec2(800, 75)),
+                align::left(0).top(525).dims(glm::uvec2(800, 75)),
+            ]
+        );
+    }
+
+    #[test]
+    fn stretch_scaling() {
+        let resolution = Resolution::new(glm::uvec2(320, 180)).scaling(Scaling::Stretch);
+        let viewport = resolution.viewport(glm::uvec2(640, 540));
+        assert_eq!(viewport.scale, glm::dvec2(2., 3.));
+        assert_eq!(viewport.offset, glm::dvec2(0., 0.));
+        assert!(viewport.bars().is_empty());
+    }
+
+    #[test]
+    fn converts_to_logical() {
+        let viewport = Resolution::new(glm::uvec2(320, 180)).viewport(glm::uvec2(1000, 600));
+        assert_eq!(viewport.to_logical(glm::ivec2(20, 30)), glm::ivec2(0, 0));
+        assert_eq!(
+            viewport.to_logical(glm::ivec2(979, 569)),
+            glm::ivec2(319, 179)
+        );
+        assert_eq!(viewport.to_logical(glm::ivec2(10, 30)), glm::ivec2(-4, 0));
+    }
+
+    #[test]
+    fn draws_letterboxed() {
+        let viewport = Resolution::new(glm::uvec2(320, 180)).viewport(glm::uvec2(1000, 600));
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        let dst = align::left(5).top(5).dims(texture.dims);
+        let color = ColorRGBA(0, 0, 0, 255);
+
+        let mut canvas = MockCanvas::new();
+        {
+            let mut renderer = viewport.renderer(&mut canvas);
+            assert!(renderer.draw(&texture, options::at(dst)).is_ok());
+        }
+        assert!(viewport.letterbox(&mut canvas, color).is_ok());
+
+        let expected = align::left(35).top(45).dims(glm::uvec2(30, 30));
+        assert_eq!(canvas.draw, vec![(texture, options::at(expected))]);
+        assert_eq!(canvas.color, Some(color));
+        assert_eq!(canvas.fill, viewport.bars());
+        assert_eq!(canvas.fill.len(), 4);
+    }
+}
@@ -0,0 +1,123 @@
+use super::{
+    align, options::Rotation, Clip, ColorRGBA, Depth, Destination, Draw, FDestination, Options,
+    Renderer, Show,
+};
+use crate::Result;
+
+/// Maps everything drawn through a `Transformed` renderer before it reaches
+/// the renderer being wrapped
+pub trait Transform {
+    fn destination(&self, dst: Destination) -> Destination;
+
+    fn point(&self, point: glm::IVec2) -> glm::IVec2 {
+        let dst = align::left(point.x).top(point.y).dims(glm::uvec2(0, 0));
+        let dst = self.destination(dst);
+        glm::ivec2(dst.left(), dst.top())
+    }
+
+    /// Transforms that can keep sub-pixel precision should override this
+    fn precise(&self, dst: FDestination) -> FDestination {
+        self.destination(dst.round()).into()
+    }
+
+    /// Rotation centers are relative to the destination so only transforms
+    /// that resize need to touch them
+    fn rotation(&self, rotation: Rotation) -> Rotation {
+        rotation
+    }
+
+    fn options(&self, mut options: Options) -> Options {
+        options = match (options.precise_dst(), options.dst) {
+            (Some(fdst), _) => options.at_precise(self.precise(fdst)),
+            (None, Some(dst)) => options.at(self.destination(dst)),
+            (None, None) => options,
+        };
+        options.rotation = options.rotation.map(|r| self.rotation(r));
+        options
+    }
+}
+
+pub struct Transformed<'a, R, T> {
+    renderer: &'a mut R,
+    transform: T,
+}
+
+impl<'a, R, T> Transformed<'a, R, T> {
+    pub fn new(renderer: &'a mut R, transform: T) -> Self {
+        Transformed {
+            renderer,
+            transform,
+        }
+    }
+}
+
+impl<R: Renderer, T: Transform> Transformed<'_, R, T> {
+    /// Textures implement `Draw` for transformed renderers by forwarding
+    /// themselves to the wrapped renderer through this
+    pub fn draw_inner(&mut self, asset: &impl Draw<R>, options: Options) -> Result<()> {
+        let options = self.transform.options(options);
+        self.renderer.draw(asset, options)
+    }
+
+    pub fn draw_all_inner(&mut self, asset: &impl Draw<R>, options: &[Options]) -> Result<()> {
+        let options: Vec<_> = options
+            .iter()
+            .map(|o| self.transform.options(o.clone()))
+            .collect();
+        asset.draw_all(&options, self.renderer)
+    }
+
+    pub fn show_inner(&mut self, asset: &impl Show<R>) -> Result<()> {
+        self.renderer.show(asset)
+    }
+}
+
+impl<R: Renderer, T: Transform> Renderer for Transformed<'_, R, T> {
+    fn set_draw_color(&mut self, color: ColorRGBA) {
+        self.renderer.set_draw_color(color)
+    }
+
+    fn fill_rects(&mut self, rects: &[Destination]) -> Result<()> {
+        let rects: Vec<_> = rects
+            .iter()
+            .map(|&r| self.transform.destination(r))
+            .collect();
+        self.renderer.fill_rects(&rects)
+    }
+
+    fn draw_rects(&mut self, rects: &[Destination]) -> Result<()> {
+        let rects: Vec<_> = rects
+            .iter()
+            .map(|&r| self.transform.destination(r))
+            .collect();
+        self.renderer.draw_rects(&rects)
+    }
+
+    fn draw_lines(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points: Vec<_> = points.iter().map(|&p| self.transform.point(p)).collect();
+        self.renderer.draw_lines(&points)
+    }
+
+    fn draw_points(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points: Vec<_> = points.iter().map(|&p| self.transform.point(p)).collect();
+        self.renderer.draw_points(&points)
+    }
+
+    fn fill_polygon(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points: Vec<_> = points.iter().map(|&p| self.transform.point(p)).collect();
+        self.renderer.fill_polygon(&points)
+    }
+
+    fn push_clip(&mut self, rect: Destination) -> Clip {
+        let rect = self.transform.destination(rect);
+        self.renderer.push_clip(rect)
+    }
+
+    fn pop_clip(&mut self, clip: Clip) {
+        self.renderer.pop_clip(clip)
+    }
+
+    fn set_depth(&mut self, depth: Depth) -> Depth {
+        self.renderer.set_depth(depth)
+    }
+}
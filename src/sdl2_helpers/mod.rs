@@ -6,6 +6,7 @@ use crate::{renderer, Result};
 
 use sdl2::{
     pixels,
+    rect::{Point, Rect},
     render::{self, RenderTarget},
 };
 
@@ -35,6 +36,39 @@ impl<T: RenderTarget> renderer::Renderer for render::Canvas<T> {
         self.draw_rects(&rects.iter().cloned().map(Into::into).collect::<Vec<_>>())
             .map_err(failure::err_msg)
     }
+
+    fn draw_lines(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points: Vec<_> = points.iter().map(|p| Point::new(p.x, p.y)).collect();
+        self.draw_lines(points.as_slice()).map_err(failure::err_msg)
+    }
+
+    fn draw_points(&mut self, points: &[glm::IVec2]) -> Result<()> {
+        let points: Vec<_> = points.iter().map(|p| Point::new(p.x, p.y)).collect();
+        self.draw_points(points.as_slice())
+            .map_err(failure::err_msg)
+    }
+
+    fn push_clip(&mut self, rect: renderer::Destination) -> renderer::Clip {
+        let previous = self.clip_rect().map(|r| {
+            renderer::align::left(r.x())
+                .top(r.y())
+                .dims(glm::uvec2(r.width(), r.height()))
+        });
+        let clip = previous.map_or(rect, |p| p.intersection(&rect));
+        // sdl2 rects are at least 1x1 so an empty clip is moved offscreen
+        let clip = if clip.dims.x == 0 || clip.dims.y == 0 {
+            Rect::new(-2, -2, 1, 1)
+        } else {
+            clip.into()
+        };
+        self.set_clip_rect(clip);
+        renderer::Clip(previous)
+    }
+
+    fn pop_clip(&mut self, clip: renderer::Clip) {
+        let renderer::Clip(previous) = clip;
+        self.set_clip_rect(previous.map(Rect::from));
+    }
 }
 
 impl<T: RenderTarget> renderer::Window for render::Canvas<T> {
@@ -5,9 +5,12 @@ use crate::{
 
 use sdl2::{
     image::LoadTexture,
+    pixels::Color,
     rect::{Point, Rect},
-    render::{Canvas, RenderTarget, Texture, TextureCreator},
+    render::{BlendMode, Canvas, RenderTarget, Texture, TextureCreator},
+    sys,
 };
+use std::rc::Rc;
 
 impl moho::Texture for Texture<'_> {
     fn dims(&self) -> glm::UVec2 {
@@ -20,6 +23,32 @@ impl<'c, T> moho::Loader<'c> for TextureCreator<T> {
     type Texture = Texture<'c>;
 }
 
+impl<'c, T> moho::TargetLoader<'c> for TextureCreator<T> {
+    fn target(&'c self, dims: glm::UVec2) -> Result<Texture<'c>> {
+        let mut texture = self.create_texture_target(None, dims.x, dims.y)?;
+        texture.set_blend_mode(BlendMode::Blend);
+        Ok(texture)
+    }
+}
+
+impl<T: RenderTarget> renderer::Target<Texture<'_>> for Canvas<T> {
+    fn render_to(
+        &mut self,
+        texture: &mut Texture<'_>,
+        scene: &impl renderer::Show<Self>,
+    ) -> Result<()> {
+        let mut shown = Ok(());
+        self.with_texture_canvas(texture, |canvas| {
+            let color = canvas.draw_color();
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+            canvas.clear();
+            canvas.set_draw_color(color);
+            shown = scene.show(canvas);
+        })?;
+        shown
+    }
+}
+
 impl<'c, T> resource::Loader<'c, Texture<'c>> for TextureCreator<T> {
     type Args = str;
 
@@ -42,27 +71,205 @@ impl From<renderer::Destination> for Rect {
 
 impl<T: RenderTarget> renderer::Draw<Canvas<T>> for Texture<'_> {
     fn draw(&self, options: renderer::Options, renderer: &mut Canvas<T>) -> Result<()> {
-        let src = options
-            .src
-            .map(|r| Rect::new(r.x as i32, r.y as i32, r.z, r.w));
-        let dst = options.dst.map(Into::into);
-        match (options.rotation, options.flip) {
-            (None, None) => renderer.copy(self, src, dst).map_err(failure::err_msg),
-            (r, f) => {
-                let (angle, center) = match r {
-                    None => (0., None),
-                    Some(r) => (r.angle, Some(Point::new(r.center.x, r.center.y))),
-                };
-                let (hflip, vflip) = match f {
-                    None => (false, false),
-                    Some(options::Flip::Horizontal) => (true, false),
-                    Some(options::Flip::Vertical) => (false, true),
-                    Some(options::Flip::Both) => (true, true),
-                };
-                renderer
-                    .copy_ex(self, src, dst, angle, center, hflip, vflip)
-                    .map_err(failure::err_msg)
+        if options.color.is_none() && options.blend.is_none() {
+            return copy(self, options, renderer);
+        }
+        let original = Modulation::of(self);
+        let copied = original
+            .with(options.color, options.blend)
+            .apply(self)
+            .and_then(|_| copy(self, options, renderer));
+        // settings may be partly applied even if applying them failed
+        let restored = original.apply(self);
+        copied.and(restored)
+    }
+
+    /// Consecutive copies that share color and blend modes only set them
+    /// once
+    fn draw_all(&self, options: &[renderer::Options], renderer: &mut Canvas<T>) -> Result<()> {
+        let original = Modulation::of(self);
+        let unmodulated = (None, None);
+        let mut current = unmodulated;
+        let copied = options.iter().try_for_each(|o| {
+            if current != (o.color, o.blend) {
+                current = (o.color, o.blend);
+                original.with(o.color, o.blend).apply(self)?;
             }
+            copy(self, o.clone(), renderer)
+        });
+        if current == unmodulated {
+            copied
+        } else {
+            copied.and(original.apply(self))
+        }
+    }
+}
+
+fn copy<T: RenderTarget>(
+    texture: &Texture<'_>,
+    options: renderer::Options,
+    renderer: &mut Canvas<T>,
+) -> Result<()> {
+    let src = options
+        .src
+        .map(|r| Rect::new(r.x as i32, r.y as i32, r.z, r.w));
+    // sdl2 0.32 has no float rects so `fdst` is drawn through its rounded `dst`
+    let dst = options.dst.map(Into::into);
+    match (options.rotation, options.flip) {
+        (None, None) => renderer.copy(texture, src, dst).map_err(failure::err_msg),
+        (r, f) => {
+            let (angle, center) = match r {
+                None => (0., None),
+                Some(r) => (r.angle, Some(Point::new(r.center.x, r.center.y))),
+            };
+            let (hflip, vflip) = match f {
+                None => (false, false),
+                Some(options::Flip::Horizontal) => (true, false),
+                Some(options::Flip::Vertical) => (false, true),
+                Some(options::Flip::Both) => (true, true),
+            };
+            renderer
+                .copy_ex(texture, src, dst, angle, center, hflip, vflip)
+                .map_err(failure::err_msg)
+        }
+    }
+}
+
+/// SDL keeps color, alpha and blend modes on the texture itself rather than
+/// per copy, so they are set right before copying and put back right after.
+/// The safe setters need a mutable texture which `Draw` does not get.
+#[derive(Clone, Copy)]
+struct Modulation {
+    color: (u8, u8, u8),
+    alpha: u8,
+    blend: Option<sys::SDL_BlendMode>,
+}
+
+impl Modulation {
+    fn of(texture: &Texture<'_>) -> Self {
+        let blend = match texture.blend_mode() {
+            BlendMode::None => Some(sys::SDL_BlendMode::SDL_BLENDMODE_NONE),
+            BlendMode::Blend => Some(sys::SDL_BlendMode::SDL_BLENDMODE_BLEND),
+            BlendMode::Add => Some(sys::SDL_BlendMode::SDL_BLENDMODE_ADD),
+            BlendMode::Mod => Some(sys::SDL_BlendMode::SDL_BLENDMODE_MOD),
+            // SDL refuses to set an invalid mode so there is nothing to restore
+            BlendMode::Invalid => None,
+        };
+        Modulation {
+            color: texture.color_mod(),
+            alpha: texture.alpha_mod(),
+            blend,
+        }
+    }
+
+    fn with(mut self, color: Option<renderer::ColorRGBA>, blend: Option<options::Blend>) -> Self {
+        if let Some(renderer::ColorRGBA(r, g, b, a)) = color {
+            self.color = (r, g, b);
+            self.alpha = a;
+        }
+        if let Some(blend) = blend {
+            self.blend = Some(match blend {
+                options::Blend::None => sys::SDL_BlendMode::SDL_BLENDMODE_NONE,
+                options::Blend::Alpha => sys::SDL_BlendMode::SDL_BLENDMODE_BLEND,
+                options::Blend::Add => sys::SDL_BlendMode::SDL_BLENDMODE_ADD,
+                options::Blend::Mod => sys::SDL_BlendMode::SDL_BLENDMODE_MOD,
+            });
         }
+        self
+    }
+
+    /// Sets every mode even if an earlier one fails, returning the first
+    /// failure
+    fn apply(self, texture: &Texture<'_>) -> Result<()> {
+        let (r, g, b) = self.color;
+        let raw = texture.raw();
+        let mut error = None;
+        let mut check = |code: i32| {
+            if code != 0 && error.is_none() {
+                error = Some(sdl2::get_error());
+            }
+        };
+        unsafe {
+            check(sys::SDL_SetTextureColorMod(raw, r, g, b));
+            check(sys::SDL_SetTextureAlphaMod(raw, self.alpha));
+            if let Some(blend) = self.blend {
+                check(sys::SDL_SetTextureBlendMode(raw, blend));
+            }
+        }
+        match error {
+            Some(error) => Err(failure::err_msg(error)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<R, X> renderer::Show<renderer::Transformed<'_, R, X>> for Texture<'_>
+where
+    R: renderer::Renderer,
+    X: renderer::Transform,
+    Self: renderer::Show<R>,
+{
+    fn show(&self, renderer: &mut renderer::Transformed<'_, R, X>) -> Result<()> {
+        renderer.show_inner(self)
+    }
+}
+
+impl<R, X> renderer::Draw<renderer::Transformed<'_, R, X>> for Texture<'_>
+where
+    R: renderer::Renderer,
+    X: renderer::Transform,
+    Self: renderer::Draw<R>,
+{
+    fn draw(
+        &self,
+        options: renderer::Options,
+        renderer: &mut renderer::Transformed<'_, R, X>,
+    ) -> Result<()> {
+        renderer.draw_inner(self, options)
+    }
+
+    fn draw_all(
+        &self,
+        options: &[renderer::Options],
+        renderer: &mut renderer::Transformed<'_, R, X>,
+    ) -> Result<()> {
+        renderer.draw_all_inner(self, options)
+    }
+}
+
+/// Queued draws outlive the `show` that made them so textures are queued
+/// by sharing them
+impl<'t, 'c: 't, R> renderer::Show<renderer::Queue<'t, R>> for Rc<Texture<'c>>
+where
+    R: renderer::Renderer,
+    Texture<'c>: renderer::Show<R>,
+{
+    fn show(&self, queue: &mut renderer::Queue<'t, R>) -> Result<()> {
+        let texture = Rc::clone(self);
+        queue.push(move |renderer| texture.show(renderer));
+        Ok(())
+    }
+}
+
+impl<'t, 'c: 't, R> renderer::Draw<renderer::Queue<'t, R>> for Rc<Texture<'c>>
+where
+    R: renderer::Renderer,
+    Texture<'c>: renderer::Draw<R>,
+{
+    fn draw(&self, options: renderer::Options, queue: &mut renderer::Queue<'t, R>) -> Result<()> {
+        let texture = Rc::clone(self);
+        queue.push(move |renderer| texture.draw(options, renderer));
+        Ok(())
+    }
+
+    fn draw_all(
+        &self,
+        options: &[renderer::Options],
+        queue: &mut renderer::Queue<'t, R>,
+    ) -> Result<()> {
+        let texture = Rc::clone(self);
+        let options = options.to_vec();
+        queue.push(move |renderer| texture.draw_all(&options, renderer));
+        Ok(())
     }
 }
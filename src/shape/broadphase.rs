@@ -0,0 +1,284 @@
+use super::{Intersect, Rectangle, Shape};
+use crate::Result;
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Widest span of cells that bounds are bucketed into; anything larger is
+/// kept aside and checked against everything instead
+const MAX_SPAN: i64 = 64;
+
+type Cell = (i64, i64);
+
+/// Uniform grid that buckets handles by the cells their bounding rectangles
+/// cover, so that only handles sharing a cell need a narrowphase check
+pub struct Grid<K> {
+    size: f64,
+    cells: HashMap<Cell, Vec<K>>,
+    /// Handles too large or unbounded to bucket
+    oversized: Vec<K>,
+    bounds: HashMap<K, Rectangle>,
+}
+
+impl<K: Copy + Eq + Hash + Ord> Grid<K> {
+    /// Grid of square cells `size` wide; about twice the size of a typical
+    /// object works well
+    pub fn new(size: f64) -> Result<Self> {
+        if !(size > 0. && size.is_finite()) {
+            return Err(failure::err_msg("grid cells must have a positive size"));
+        }
+        Ok(Grid {
+            size,
+            cells: HashMap::new(),
+            oversized: vec![],
+            bounds: HashMap::new(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    pub fn bounds(&self, key: K) -> Option<&Rectangle> {
+        self.bounds.get(&key)
+    }
+
+    /// Adds `key`, or moves it if it was already in the grid
+    pub fn insert(&mut self, key: K, bounds: Rectangle) {
+        if let Some(old) = self.bounds.insert(key, bounds) {
+            if self.cover(&old) == self.cover(&bounds) {
+                return;
+            }
+            self.unlink(key, &old);
+        }
+        match self.cover(&bounds) {
+            Some((from, to)) => {
+                for cell in cells(from, to) {
+                    self.cells.entry(cell).or_default().push(key);
+                }
+            }
+            None => self.oversized.push(key),
+        }
+    }
+
+    /// Moves `key` to `bounds`; returns false if it was not in the grid
+    pub fn update(&mut self, key: K, bounds: Rectangle) -> bool {
+        let present = self.bounds.contains_key(&key);
+        if present {
+            self.insert(key, bounds);
+        }
+        present
+    }
+
+    pub fn remove(&mut self, key: K) -> Option<Rectangle> {
+        let bounds = self.bounds.remove(&key)?;
+        self.unlink(key, &bounds);
+        Some(bounds)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.oversized.clear();
+        self.bounds.clear();
+    }
+
+    /// Handles whose bounds overlap `region`, sorted
+    pub fn query(&self, region: &Rectangle) -> Vec<K> {
+        let candidates: Vec<K> = match self.cover(region) {
+            Some((from, to)) => cells(from, to)
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .chain(&self.oversized)
+                .copied()
+                .collect(),
+            None => self.bounds.keys().copied().collect(),
+        };
+        let mut found: Vec<_> = candidates
+            .into_iter()
+            .filter(|key| self.bounds[key].intersects(region))
+            .collect();
+        found.sort();
+        found.dedup();
+        found
+    }
+
+    /// Handles whose bounds contain `point`, sorted
+    pub fn query_point(&self, point: glm::DVec2) -> Vec<K> {
+        let cell = self.cell(point);
+        let mut found: Vec<_> = self
+            .cells
+            .get(&cell)
+            .into_iter()
+            .flatten()
+            .chain(&self.oversized)
+            .copied()
+            .filter(|key| self.bounds[key].contains(&point))
+            .collect();
+        found.sort();
+        found
+    }
+
+    /// Every pair of handles whose bounds overlap, each once with the lower
+    /// handle first; check these with `Intersect::mtv` to find collisions
+    pub fn pairs(&self) -> Vec<(K, K)> {
+        let bucketed = self.cells.values().flat_map(|keys| {
+            keys.iter()
+                .enumerate()
+                .flat_map(move |(i, &a)| keys[i + 1..].iter().map(move |&b| (a, b)))
+        });
+        let oversized = self
+            .oversized
+            .iter()
+            .flat_map(|&a| self.bounds.keys().map(move |&b| (a, b)))
+            .filter(|(a, b)| a != b);
+        let mut pairs: Vec<_> = bucketed
+            .chain(oversized)
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .filter(|(a, b)| self.bounds[a].intersects(&self.bounds[b]))
+            .collect();
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+
+    fn unlink(&mut self, key: K, bounds: &Rectangle) {
+        let (from, to) = match self.cover(bounds) {
+            Some(cover) => cover,
+            None => {
+                self.oversized.retain(|&k| k != key);
+                return;
+            }
+        };
+        for cell in cells(from, to) {
+            if let Some(keys) = self.cells.get_mut(&cell) {
+                keys.retain(|&k| k != key);
+                if keys.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+    }
+
+    fn cell(&self, point: glm::DVec2) -> Cell {
+        let cell = glm::floor(point / self.size);
+        (cell.x as i64, cell.y as i64)
+    }
+
+    /// First and last cells covered by `bounds`, or `None` if they span too
+    /// many cells or are not finite
+    fn cover(&self, bounds: &Rectangle) -> Option<(Cell, Cell)> {
+        let bottom_right = bounds.top_left + bounds.dims;
+        let finite = |v: glm::DVec2| v.x.is_finite() && v.y.is_finite();
+        if !finite(bounds.top_left) || !finite(bottom_right) {
+            return None;
+        }
+        let (from, to) = (self.cell(bounds.top_left), self.cell(bottom_right));
+        if to.0 - from.0 >= MAX_SPAN || to.1 - from.1 >= MAX_SPAN {
+            None
+        } else {
+            Some((from, to))
+        }
+    }
+}
+
+fn cells(from: Cell, to: Cell) -> impl Iterator<Item = Cell> {
+    (from.1..=to.1).flat_map(move |y| (from.0..=to.0).map(move |x| (x, y)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> Rectangle {
+        Rectangle {
+            top_left: glm::dvec2(x, y),
+            dims: glm::dvec2(w, h),
+        }
+    }
+
+    fn grid() -> Grid<u32> {
+        let mut grid = Grid::new(10.).unwrap();
+        grid.insert(1, rect(0., 0., 4., 4.));
+        grid.insert(2, rect(3., 3., 4., 4.));
+        grid.insert(3, rect(25., 25., 4., 4.));
+        grid.insert(4, rect(-15., 5., 30., 2.));
+        grid
+    }
+
+    #[test]
+    fn queries_region() {
+        let grid = grid();
+        assert_eq!(grid.len(), 4);
+        assert_eq!(grid.query(&rect(-1., -1., 3., 3.)), vec![1]);
+        assert_eq!(grid.query(&rect(5., 5., 1., 1.)), vec![2, 4]);
+        assert_eq!(grid.query(&rect(-20., -20., 60., 60.)), vec![1, 2, 3, 4]);
+        assert!(grid.query(&rect(40., 40., 5., 5.)).is_empty());
+    }
+
+    #[test]
+    fn queries_point() {
+        let grid = grid();
+        assert_eq!(grid.query_point(glm::dvec2(3.5, 3.5)), vec![1, 2]);
+        assert_eq!(grid.query_point(glm::dvec2(-10., 6.)), vec![4]);
+        assert!(grid.query_point(glm::dvec2(20., 20.)).is_empty());
+    }
+
+    #[test]
+    fn reports_overlapping_pairs_once() {
+        let grid = grid();
+        assert_eq!(grid.pairs(), vec![(1, 2), (2, 4)]);
+    }
+
+    #[test]
+    fn moves_handles() {
+        let mut grid = grid();
+        assert!(grid.update(3, rect(0.5, 0.5, 2., 2.)));
+        assert!(grid.query_point(glm::dvec2(26., 26.)).is_empty());
+        assert_eq!(grid.pairs(), vec![(1, 2), (1, 3), (2, 4)]);
+        assert!(!grid.update(9, rect(1., 1., 2., 2.)));
+        assert_eq!(grid.len(), 4);
+    }
+
+    #[test]
+    fn rejects_empty_cells() {
+        assert!(Grid::<u32>::new(0.).is_err());
+        assert!(Grid::<u32>::new(-1.).is_err());
+        assert!(Grid::<u32>::new(f64::NAN).is_err());
+        assert!(Grid::<u32>::new(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn keeps_oversized_bounds_aside() {
+        let mut grid = grid();
+        let floor = rect(-1e9, 2., 2e9, 1.);
+        let sky = Rectangle {
+            top_left: glm::dvec2(f64::NEG_INFINITY, -50.),
+            dims: glm::dvec2(f64::INFINITY, 10.),
+        };
+        grid.insert(5, floor);
+        grid.insert(6, sky);
+        assert!(grid.cells.values().all(|keys| !keys.contains(&5)));
+        assert_eq!(grid.query(&rect(5., 2., 1., 1.)), vec![2, 5]);
+        assert_eq!(grid.query_point(glm::dvec2(1e6, 2.5)), vec![5]);
+        assert_eq!(grid.pairs(), vec![(1, 2), (1, 5), (2, 4), (2, 5)]);
+        assert_eq!(grid.query(&rect(-1e12, -1e12, 2e12, 2e12)).len(), 6);
+
+        assert!(grid.update(5, rect(0., 0., 1., 1.)));
+        assert!(grid.oversized.iter().all(|&k| k != 5));
+        assert!(grid.remove(6).is_some());
+        assert!(grid.oversized.is_empty());
+    }
+
+    #[test]
+    fn removes_handles() {
+        let mut grid = grid();
+        let removed = grid.remove(2).map(|r| r.top_left);
+        assert_eq!(removed, Some(glm::dvec2(3., 3.)));
+        assert!(grid.pairs().is_empty());
+        assert!(grid.cells.values().all(|keys| !keys.contains(&2)));
+        assert_eq!(grid.remove(2).map(|r| r.top_left), None);
+    }
+}
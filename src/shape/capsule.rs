@@ -0,0 +1,255 @@
+use super::{
+    capsule_mtv, rounded_mtv, Circle, Intersect, OrientedRectangle, Polygon, Rectangle, Segment,
+    Shape,
+};
+
+/// Every point within `radius` of `segment`: a rectangle with round ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capsule {
+    pub segment: Segment,
+    pub radius: f64,
+}
+
+impl Capsule {
+    pub fn new(start: glm::DVec2, end: glm::DVec2, radius: f64) -> Self {
+        Capsule {
+            segment: Segment::new(start, end),
+            radius,
+        }
+    }
+
+    /// Upright capsule `height` tall overall, as used for characters
+    pub fn vertical(center: glm::DVec2, height: f64, radius: f64) -> Self {
+        let half = glm::dvec2(0., (height / 2. - radius).max(0.));
+        Capsule::new(center - half, center + half, radius)
+    }
+}
+
+impl Shape for Capsule {
+    type Rotated = Capsule;
+
+    fn center(&self) -> glm::DVec2 {
+        self.segment.center()
+    }
+
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        glm::distance(self.segment.closest_point(*point), *point) < self.radius
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> Capsule {
+        Capsule {
+            segment: self.segment.nudge(nudge),
+            ..self
+        }
+    }
+
+    fn center_at(self, center: glm::DVec2) -> Capsule {
+        Capsule {
+            segment: self.segment.center_at(center),
+            ..self
+        }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        let bounds = self.segment.aabb();
+        Rectangle {
+            top_left: bounds.top_left - self.radius,
+            dims: bounds.dims + self.radius * 2.,
+        }
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Capsule {
+        Capsule {
+            segment: self.segment.rotate(angle, pivot),
+            ..self
+        }
+    }
+
+    fn scale(self, factor: f64) -> Capsule {
+        Capsule {
+            segment: self.segment.scale(factor),
+            radius: self.radius * factor,
+        }
+    }
+}
+
+impl Intersect<Capsule> for Capsule {
+    fn intersects(&self, other: &Capsule) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        capsule_mtv(&self.segment, self.radius, &fixed.segment, fixed.radius)
+    }
+}
+
+impl Intersect<Segment> for Capsule {
+    fn intersects(&self, other: &Segment) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        capsule_mtv(&self.segment, self.radius, fixed, 0.)
+    }
+}
+
+impl Intersect<Circle> for Capsule {
+    fn intersects(&self, other: &Circle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Circle) -> Option<glm::DVec2> {
+        let center = Segment::new(fixed.center, fixed.center);
+        capsule_mtv(&self.segment, self.radius, &center, fixed.radius)
+    }
+}
+
+impl Intersect<Rectangle> for Capsule {
+    fn intersects(&self, other: &Rectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Rectangle) -> Option<glm::DVec2> {
+        rounded_mtv(&self.segment, self.radius, fixed)
+    }
+}
+
+impl Intersect<OrientedRectangle> for Capsule {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        rounded_mtv(&self.segment, self.radius, fixed)
+    }
+}
+
+impl Intersect<Polygon> for Capsule {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        rounded_mtv(&self.segment, self.radius, fixed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::assert_near;
+
+    #[test]
+    fn capsule_contains() {
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        assert_eq!(capsule.segment.start, glm::dvec2(0., -2.));
+        assert!(capsule.contains(&glm::dvec2(0.9, 1.5)));
+        assert!(capsule.contains(&glm::dvec2(0., 2.9)));
+        assert!(!capsule.contains(&glm::dvec2(0.9, 2.9)));
+    }
+
+    #[test]
+    fn capsule_capsule_no_intersect() {
+        let a = Capsule::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.), 1.);
+        let b = Capsule::new(glm::dvec2(5.5, 1.5), glm::dvec2(8., 4.), 1.);
+        assert!(!a.intersects(&b));
+        assert_eq!(a.mtv(&b), None);
+        assert!(!b.intersects(&a));
+        assert_eq!(b.mtv(&a), None);
+    }
+
+    #[test]
+    fn capsule_capsule_intersect() {
+        let a = Capsule::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.), 1.);
+        let b = Capsule::new(glm::dvec2(2., 1.5), glm::dvec2(2., 5.), 1.);
+        assert!(a.intersects(&b));
+        assert_eq!(a.mtv(&b), Some(glm::dvec2(0., -0.5)));
+        assert_eq!(b.mtv(&a), Some(glm::dvec2(0., 0.5)));
+    }
+
+    #[test]
+    fn crossing_capsules() {
+        let a = Capsule::new(glm::dvec2(0., 0.), glm::dvec2(5., 0.), 1.);
+        let b = Capsule::new(glm::dvec2(3., -3.), glm::dvec2(3., 1.), 0.5);
+        assert!(a.intersects(&b));
+        assert_eq!(a.mtv(&b), Some(glm::dvec2(0., 2.5)));
+        assert_eq!(b.mtv(&a), Some(glm::dvec2(0., -2.5)));
+    }
+
+    #[test]
+    fn capsule_circle_intersect() {
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(1.5, 1.),
+        };
+        assert!(capsule.intersects(&circle));
+        assert_eq!(capsule.mtv(&circle), Some(glm::dvec2(-0.5, 0.)));
+        assert_eq!(circle.mtv(&capsule), Some(glm::dvec2(0.5, 0.)));
+
+        let past_end = circle.center_at(glm::dvec2(1.5, 3.5));
+        assert!(!capsule.intersects(&past_end));
+        assert_eq!(past_end.mtv(&capsule), None);
+    }
+
+    #[test]
+    fn capsule_rectangle_no_intersect() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(1., 3.),
+        };
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        assert!(!capsule.intersects(&rectangle));
+        assert_eq!(rectangle.mtv(&capsule), None);
+    }
+
+    #[test]
+    fn capsule_rectangle_intersect() {
+        let floor = Rectangle {
+            dims: glm::dvec2(10., 2.),
+            top_left: glm::dvec2(-5., 2.5),
+        };
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        assert!(capsule.intersects(&floor));
+        assert_near(capsule.mtv(&floor), glm::dvec2(0., -0.5));
+        assert_near(floor.mtv(&capsule), glm::dvec2(0., 0.5));
+    }
+
+    #[test]
+    fn capsule_rectangle_corner() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(0.5, 2.5),
+        };
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        let depth = 1. - 0.5_f64.hypot(0.5);
+        assert!(capsule.intersects(&rectangle));
+        assert_near(
+            capsule.mtv(&rectangle),
+            glm::dvec2(-depth, -depth) / 2_f64.sqrt(),
+        );
+    }
+
+    #[test]
+    fn capsule_polygon_intersect() {
+        let roof = Polygon::new(vec![
+            glm::dvec2(-5., 2.5),
+            glm::dvec2(5., 2.5),
+            glm::dvec2(0., 8.),
+        ])
+        .unwrap();
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        assert!(capsule.intersects(&roof));
+        assert_near(capsule.mtv(&roof), glm::dvec2(0., -0.5));
+        assert_near(roof.mtv(&capsule), glm::dvec2(0., 0.5));
+        assert!(!capsule.nudge(glm::dvec2(0., -1.)).intersects(&roof));
+    }
+
+    #[test]
+    fn center_at() {
+        let capsule = Capsule::new(glm::dvec2(0., 0.), glm::dvec2(4., 2.), 1.);
+        let moved = capsule.center_at(glm::dvec2(-1.5, 0.7));
+        assert_eq!(moved.center(), glm::dvec2(-1.5, 0.7));
+        assert_eq!(moved.radius, 1.);
+    }
+}
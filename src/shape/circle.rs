@@ -1,4 +1,7 @@
-use super::{Axis, FindMtv, Intersect, Line, Rectangle, Shape};
+use super::{
+    capsule_mtv, circle_mtv, rotate_around, Axis, Capsule, FindMtv, Intersect, Line,
+    OrientedRectangle, Polygon, Rectangle, Segment, Shape,
+};
 
 use std::iter;
 
@@ -9,6 +12,8 @@ pub struct Circle {
 }
 
 impl Shape for Circle {
+    type Rotated = Circle;
+
     fn center(&self) -> glm::DVec2 {
         self.center
     }
@@ -28,6 +33,23 @@ impl Shape for Circle {
     fn center_at(self, center: glm::DVec2) -> Circle {
         Circle { center, ..self }
     }
+
+    fn aabb(&self) -> Rectangle {
+        Rectangle {
+            top_left: self.center - self.radius,
+            dims: glm::dvec2(self.radius, self.radius) * 2.,
+        }
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Circle {
+        let center = rotate_around(self.center, angle, pivot);
+        Circle { center, ..self }
+    }
+
+    fn scale(self, factor: f64) -> Circle {
+        let radius = self.radius * factor;
+        Circle { radius, ..self }
+    }
 }
 
 impl Intersect<Rectangle> for Circle {
@@ -98,26 +120,56 @@ impl Intersect<Line> for Circle {
     }
 
     fn mtv(&self, fixed: &Line) -> Option<glm::DVec2> {
-        //TODO: Check for endpoints. Will have to run mtv around those as well
-        //Current implementation assumes infinite line
-        let len = fixed.1 - fixed.0;
-        let normal = glm::normalize(len);
-        let normal = glm::dvec2(-normal.y, normal.x);
-        let distance = (len.y * self.center.x - len.x * self.center.y + fixed.1.x * fixed.0.y
-            - fixed.1.y * fixed.0.x)
-            / glm::length(len);
-        if distance.abs() < self.radius {
-            let normal = if distance < 0. { normal * -1. } else { normal };
-            Some(glm::ext::normalize_to(normal, distance.abs() - self.radius))
-        } else {
-            None
-        }
+        self.mtv(&Segment::from(*fixed))
+    }
+}
+
+impl Intersect<OrientedRectangle> for Circle {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        circle_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Polygon> for Circle {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        circle_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Segment> for Circle {
+    fn intersects(&self, other: &Segment) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        let center = Segment::new(self.center, self.center);
+        capsule_mtv(&center, self.radius, fixed, 0.)
+    }
+}
+
+impl Intersect<Capsule> for Circle {
+    fn intersects(&self, other: &Capsule) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        let center = Segment::new(self.center, self.center);
+        capsule_mtv(&center, self.radius, &fixed.segment, fixed.radius)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::shape::Collide;
 
     #[test]
     fn circle_no_contains() {
@@ -273,6 +325,18 @@ mod test {
         assert_eq!(circle.mtv(&line), Some(glm::dvec2(0., 1.)));
     }
 
+    #[test]
+    fn circle_line_endpoints() {
+        let line = (glm::dvec2(2., 2.), glm::dvec2(5., 2.));
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(0.5, 2.5),
+        };
+        assert_eq!(circle.mtv(&line), None);
+        let circle = circle.center_at(glm::dvec2(1.5, 2.));
+        assert_eq!(circle.mtv(&line), Some(glm::dvec2(-0.5, 0.)));
+    }
+
     #[test]
     fn nudge() {
         let circle = Circle {
@@ -294,4 +358,41 @@ mod test {
         assert_eq!(nudged.radius, 3.);
         assert_eq!(nudged.center, glm::dvec2(-1.5, 0.7));
     }
+
+    #[test]
+    fn circle_edge_manifold() {
+        let floor = Rectangle {
+            dims: glm::dvec2(10., 5.),
+            top_left: glm::dvec2(0., 0.),
+        };
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(5., -0.5),
+        };
+        let manifold = circle.manifold(&floor).unwrap();
+        assert_eq!(manifold.normal, glm::dvec2(0., -1.));
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(manifold.points, vec![glm::dvec2(5., 0.5)]);
+
+        let manifold = floor.manifold(&circle).unwrap();
+        assert_eq!(manifold.normal, glm::dvec2(0., 1.));
+        assert_eq!(manifold.points, vec![glm::dvec2(5., 0.5)]);
+    }
+
+    #[test]
+    fn circle_corner_manifold() {
+        let floor = Rectangle {
+            dims: glm::dvec2(10., 5.),
+            top_left: glm::dvec2(0., 0.),
+        };
+        let circle = Circle {
+            radius: 1.5,
+            center: glm::dvec2(-0.6, -0.8),
+        };
+        let manifold = circle.manifold(&floor).unwrap();
+        assert!(glm::distance(manifold.normal, glm::dvec2(-0.6, -0.8)) < 0.00001);
+        assert!((manifold.depth - 0.5).abs() < 0.00001);
+        assert_eq!(manifold.points.len(), 1);
+        assert!(glm::distance(manifold.points[0], glm::dvec2(0.3, 0.4)) < 0.00001);
+    }
 }
@@ -0,0 +1,442 @@
+use super::{
+    bounds, rotate, rotate_around, Capsule, Circle, Collide, Intersect, Manifold,
+    OrientedRectangle, Polygon, Rectangle, Segment, Shape,
+};
+
+/// Any shape, so that different kinds can be stored and collided together
+#[derive(Debug, Clone)]
+pub enum Collider {
+    Rectangle(Rectangle),
+    Circle(Circle),
+    OrientedRectangle(OrientedRectangle),
+    Polygon(Polygon),
+    Segment(Segment),
+    Capsule(Capsule),
+    Compound(Compound),
+}
+
+/// Several shapes that move together, each centered at its own offset from
+/// `position`
+#[derive(Debug, Clone)]
+pub struct Compound {
+    pub position: glm::DVec2,
+    pub parts: Vec<(glm::DVec2, Collider)>,
+}
+
+macro_rules! dispatch {
+    ($collider:expr, $shape:ident => $body:expr) => {
+        match $collider {
+            Collider::Rectangle($shape) => $body,
+            Collider::Circle($shape) => $body,
+            Collider::OrientedRectangle($shape) => $body,
+            Collider::Polygon($shape) => $body,
+            Collider::Segment($shape) => $body,
+            Collider::Capsule($shape) => $body,
+            Collider::Compound($shape) => $body,
+        }
+    };
+}
+
+impl Compound {
+    pub fn new(position: glm::DVec2) -> Self {
+        Compound {
+            position,
+            parts: vec![],
+        }
+    }
+
+    /// Adds `shape` centered at `offset` from the position
+    pub fn part(mut self, offset: glm::DVec2, shape: impl Into<Collider>) -> Self {
+        self.parts.push((offset, shape.into()));
+        self
+    }
+
+    /// The parts where they currently are
+    pub fn shapes(&self) -> impl Iterator<Item = Collider> + '_ {
+        self.parts
+            .iter()
+            .map(move |(offset, shape)| shape.clone().center_at(self.position + *offset))
+    }
+}
+
+impl Shape for Compound {
+    type Rotated = Compound;
+
+    fn center(&self) -> glm::DVec2 {
+        self.position
+    }
+
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        self.shapes().any(|shape| shape.contains(point))
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> Compound {
+        Compound {
+            position: self.position + nudge,
+            ..self
+        }
+    }
+
+    fn center_at(self, center: glm::DVec2) -> Compound {
+        Compound {
+            position: center,
+            ..self
+        }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        let corners: Vec<_> = self
+            .shapes()
+            .map(|shape| shape.aabb())
+            .flat_map(|aabb| vec![aabb.top_left, aabb.top_left + aabb.dims])
+            .collect();
+        if corners.is_empty() {
+            Rectangle {
+                top_left: self.position,
+                dims: glm::dvec2(0., 0.),
+            }
+        } else {
+            bounds(&corners)
+        }
+    }
+
+    /// Turns the parts along with their offsets
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Compound {
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|(offset, shape)| {
+                let center = shape.center();
+                (rotate(offset, angle), shape.rotate(angle, center))
+            })
+            .collect();
+        Compound {
+            position: rotate_around(self.position, angle, pivot),
+            parts,
+        }
+    }
+
+    /// Scales the parts along with their offsets
+    fn scale(self, factor: f64) -> Compound {
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|(offset, shape)| (offset * factor, shape.scale(factor)))
+            .collect();
+        Compound { parts, ..self }
+    }
+}
+
+impl Shape for Collider {
+    type Rotated = Collider;
+
+    fn center(&self) -> glm::DVec2 {
+        dispatch!(self, shape => shape.center())
+    }
+
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        dispatch!(self, shape => shape.contains(point))
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> Collider {
+        dispatch!(self, shape => Collider::from(shape.nudge(nudge)))
+    }
+
+    fn center_at(self, center: glm::DVec2) -> Collider {
+        dispatch!(self, shape => Collider::from(shape.center_at(center)))
+    }
+
+    fn aabb(&self) -> Rectangle {
+        dispatch!(self, shape => shape.aabb())
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Collider {
+        dispatch!(self, shape => Collider::from(shape.rotate(angle, pivot)))
+    }
+
+    fn scale(self, factor: f64) -> Collider {
+        dispatch!(self, shape => Collider::from(shape.scale(factor)))
+    }
+}
+
+impl Intersect<Collider> for Collider {
+    fn intersects(&self, other: &Collider) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Collider) -> Option<glm::DVec2> {
+        match self {
+            Collider::Rectangle(object) => mtv(object, fixed),
+            Collider::Circle(object) => mtv(object, fixed),
+            Collider::OrientedRectangle(object) => mtv(object, fixed),
+            Collider::Polygon(object) => mtv(object, fixed),
+            Collider::Segment(object) => mtv(object, fixed),
+            Collider::Capsule(object) => mtv(object, fixed),
+            Collider::Compound(object) => object.mtv(fixed),
+        }
+    }
+}
+
+/// Compounds move out of the part they overlap the deepest
+impl Intersect<Collider> for Compound {
+    fn intersects(&self, other: &Collider) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Collider) -> Option<glm::DVec2> {
+        deepest(self.shapes().map(|shape| shape.mtv(fixed)), length)
+    }
+}
+
+impl Collide<Collider> for Collider {
+    fn manifold(&self, fixed: &Collider) -> Option<Manifold> {
+        match self {
+            Collider::Rectangle(object) => manifold(object, fixed),
+            Collider::Circle(object) => manifold(object, fixed),
+            Collider::OrientedRectangle(object) => manifold(object, fixed),
+            Collider::Polygon(object) => manifold(object, fixed),
+            Collider::Segment(object) => manifold(object, fixed),
+            Collider::Capsule(object) => manifold(object, fixed),
+            Collider::Compound(object) => object.manifold(fixed),
+        }
+    }
+}
+
+impl Collide<Collider> for Compound {
+    fn manifold(&self, fixed: &Collider) -> Option<Manifold> {
+        let manifolds = self.shapes().map(|shape| shape.manifold(fixed));
+        deepest(manifolds, |manifold| manifold.depth)
+    }
+}
+
+fn mtv<S>(object: &S, fixed: &Collider) -> Option<glm::DVec2>
+where
+    S: Intersect<Rectangle>
+        + Intersect<Circle>
+        + Intersect<OrientedRectangle>
+        + Intersect<Polygon>
+        + Intersect<Segment>
+        + Intersect<Capsule>,
+{
+    match fixed {
+        Collider::Rectangle(fixed) => object.mtv(fixed),
+        Collider::Circle(fixed) => object.mtv(fixed),
+        Collider::OrientedRectangle(fixed) => object.mtv(fixed),
+        Collider::Polygon(fixed) => object.mtv(fixed),
+        Collider::Segment(fixed) => object.mtv(fixed),
+        Collider::Capsule(fixed) => object.mtv(fixed),
+        Collider::Compound(fixed) => {
+            deepest(fixed.shapes().map(|shape| mtv(object, &shape)), length)
+        }
+    }
+}
+
+fn manifold<S>(object: &S, fixed: &Collider) -> Option<Manifold>
+where
+    S: Collide<Rectangle>
+        + Collide<Circle>
+        + Collide<OrientedRectangle>
+        + Collide<Polygon>
+        + Collide<Segment>
+        + Collide<Capsule>,
+{
+    match fixed {
+        Collider::Rectangle(fixed) => object.manifold(fixed),
+        Collider::Circle(fixed) => object.manifold(fixed),
+        Collider::OrientedRectangle(fixed) => object.manifold(fixed),
+        Collider::Polygon(fixed) => object.manifold(fixed),
+        Collider::Segment(fixed) => object.manifold(fixed),
+        Collider::Capsule(fixed) => object.manifold(fixed),
+        Collider::Compound(fixed) => {
+            let manifolds = fixed.shapes().map(|shape| manifold(object, &shape));
+            deepest(manifolds, |manifold| manifold.depth)
+        }
+    }
+}
+
+/// Skips anything whose depth is NaN rather than picking or panicking on it
+fn deepest<T>(found: impl Iterator<Item = Option<T>>, depth: impl Fn(&T) -> f64) -> Option<T> {
+    found
+        .flatten()
+        .filter(|x| !depth(x).is_nan())
+        .max_by(|x, y| depth(x).total_cmp(&depth(y)))
+}
+
+fn length(mtv: &glm::DVec2) -> f64 {
+    glm::length(*mtv)
+}
+
+impl From<Rectangle> for Collider {
+    fn from(shape: Rectangle) -> Self {
+        Collider::Rectangle(shape)
+    }
+}
+
+impl From<Circle> for Collider {
+    fn from(shape: Circle) -> Self {
+        Collider::Circle(shape)
+    }
+}
+
+impl From<OrientedRectangle> for Collider {
+    fn from(shape: OrientedRectangle) -> Self {
+        Collider::OrientedRectangle(shape)
+    }
+}
+
+impl From<Polygon> for Collider {
+    fn from(shape: Polygon) -> Self {
+        Collider::Polygon(shape)
+    }
+}
+
+impl From<Segment> for Collider {
+    fn from(shape: Segment) -> Self {
+        Collider::Segment(shape)
+    }
+}
+
+impl From<Capsule> for Collider {
+    fn from(shape: Capsule) -> Self {
+        Collider::Capsule(shape)
+    }
+}
+
+impl From<Compound> for Collider {
+    fn from(shape: Compound) -> Self {
+        Collider::Compound(shape)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::assert_near;
+
+    fn assert_bounds(shape: impl Into<Collider>, top_left: glm::DVec2, dims: glm::DVec2) {
+        let aabb = shape.into().aabb();
+        assert!(
+            glm::distance(aabb.top_left, top_left) < 0.00001,
+            "{:?}",
+            aabb
+        );
+        assert!(glm::distance(aabb.dims, dims) < 0.00001, "{:?}", aabb);
+    }
+
+    fn floor() -> Collider {
+        Collider::from(Rectangle {
+            top_left: glm::dvec2(-50., 10.),
+            dims: glm::dvec2(100., 10.),
+        })
+    }
+
+    fn cart() -> Compound {
+        let wheel = Circle {
+            center: glm::dvec2(0., 0.),
+            radius: 2.,
+        };
+        Compound::new(glm::dvec2(0., 0.))
+            .part(
+                glm::dvec2(0., 0.),
+                Rectangle {
+                    top_left: glm::dvec2(0., 0.),
+                    dims: glm::dvec2(10., 4.),
+                },
+            )
+            .part(glm::dvec2(-4., 3.), wheel)
+            .part(glm::dvec2(4., 3.), wheel)
+    }
+
+    #[test]
+    fn ignores_nan_depths() {
+        let found = vec![Some(1.), Some(f64::NAN), None, Some(3.), Some(2.)];
+        assert_eq!(deepest(found.into_iter(), |&d| d), Some(3.));
+        assert_eq!(deepest(vec![Some(f64::NAN)].into_iter(), |&d| d), None);
+    }
+
+    #[test]
+    fn bounds_shapes() {
+        let circle = Circle {
+            center: glm::dvec2(1., 2.),
+            radius: 3.,
+        };
+        assert_bounds(circle, glm::dvec2(-2., -1.), glm::dvec2(6., 6.));
+
+        let diamond = OrientedRectangle::new(glm::dvec2(0., 0.), glm::dvec2(2., 2.), 45.);
+        let half = 2_f64.sqrt();
+        assert_bounds(
+            diamond,
+            glm::dvec2(-half, -half),
+            glm::dvec2(half, half) * 2.,
+        );
+
+        let segment = Segment::new(glm::dvec2(3., -1.), glm::dvec2(-1., 2.));
+        assert_bounds(segment, glm::dvec2(-1., -1.), glm::dvec2(4., 3.));
+
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        assert_bounds(capsule, glm::dvec2(-1., -3.), glm::dvec2(2., 6.));
+
+        let triangle = Polygon::new(vec![
+            glm::dvec2(0., 0.),
+            glm::dvec2(6., 1.),
+            glm::dvec2(2., 5.),
+        ])
+        .unwrap();
+        assert_bounds(triangle, glm::dvec2(0., 0.), glm::dvec2(6., 5.));
+    }
+
+    #[test]
+    fn dispatches_to_shapes() {
+        let ball = Circle {
+            center: glm::dvec2(0., 9.),
+            radius: 2.,
+        };
+        let capsule = Capsule::vertical(glm::dvec2(20., 6.), 10., 1.);
+        let expected = ball.mtv(&capsule);
+
+        let (ball, capsule) = (Collider::from(ball), Collider::from(capsule));
+        assert_near(ball.mtv(&floor()), glm::dvec2(0., -1.));
+        assert_near(capsule.mtv(&floor()), glm::dvec2(0., -1.));
+        assert_eq!(ball.mtv(&capsule), expected);
+        assert!(!ball.intersects(&capsule));
+    }
+
+    #[test]
+    fn places_parts_at_offsets() {
+        let cart = cart().nudge(glm::dvec2(10., 0.));
+        assert!(cart.contains(&glm::dvec2(6., 4.)));
+        assert!(!cart.contains(&glm::dvec2(10., 4.5)));
+        assert_eq!(
+            cart.clone().center_at(glm::dvec2(1., 1.)).position,
+            glm::dvec2(1., 1.)
+        );
+
+        let aabb = cart.aabb();
+        assert_eq!(aabb.top_left, glm::dvec2(4., -2.));
+        assert_eq!(aabb.dims, glm::dvec2(12., 7.));
+        assert_eq!(
+            Compound::new(glm::dvec2(3., 3.)).aabb().top_left,
+            glm::dvec2(3., 3.)
+        );
+    }
+
+    #[test]
+    fn compounds_move_out_of_deepest_part() {
+        let cart = Collider::from(cart().nudge(glm::dvec2(0., 6.)));
+        assert_near(cart.mtv(&floor()), glm::dvec2(0., -1.));
+        assert_near(floor().mtv(&cart), glm::dvec2(0., 1.));
+
+        let stacked = cart.clone().nudge(glm::dvec2(7., -5.5));
+        assert_near(stacked.mtv(&cart), glm::dvec2(0., -1.5));
+        assert!(!cart.intersects(&cart.clone().nudge(glm::dvec2(0., -10.))));
+    }
+
+    #[test]
+    fn compounds_touch_with_deepest_part() {
+        let cart = Collider::from(cart().nudge(glm::dvec2(0., 6.)));
+        let manifold = cart.manifold(&floor()).unwrap();
+        assert_near(Some(manifold.normal), glm::dvec2(0., -1.));
+        assert_eq!(manifold.points.len(), 1);
+        assert_near(manifold.points.first().copied(), glm::dvec2(4., 11.));
+    }
+}
@@ -0,0 +1,252 @@
+use super::{
+    sweep::Outline, Capsule, Circle, Intersect, OrientedRectangle, Polygon, Rectangle, Segment,
+};
+
+/// How far a contact point may stick out of the other shape and still count
+const EPSILON: f64 = 1e-9;
+
+/// Where and how deep two overlapping shapes touch, so that stacks can rest
+/// on several points and off center hits can spin
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifold {
+    /// Unit direction that moves the object out of the fixed shape
+    pub normal: glm::DVec2,
+    /// Length of the mtv along `normal`
+    pub depth: f64,
+    /// One or two points of either shape that are deepest inside the other
+    pub points: Vec<glm::DVec2>,
+}
+
+/// Contact manifold of a shape overlapping a fixed one, agreeing with its
+/// `Intersect::mtv`
+pub trait Collide<S> {
+    fn manifold(&self, fixed: &S) -> Option<Manifold>;
+}
+
+macro_rules! collide {
+    ($($object:ty => $($fixed:ty),+;)+) => {
+        $($(
+            impl Collide<$fixed> for $object {
+                fn manifold(&self, fixed: &$fixed) -> Option<Manifold> {
+                    manifold(self, fixed)
+                }
+            }
+        )+)+
+    };
+}
+
+collide! {
+    Rectangle => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+    Circle => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+    OrientedRectangle => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+    Polygon => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+    Segment => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+    Capsule => Rectangle, Circle, OrientedRectangle, Polygon, Segment, Capsule;
+}
+
+/// Clips the edge of one shape facing the other against the edge of the
+/// other that faces back; whichever is flatter against the normal is the
+/// reference that the other gets clipped to
+pub(super) fn manifold<M, F>(object: &M, fixed: &F) -> Option<Manifold>
+where
+    M: Outline + Intersect<F>,
+    F: Outline,
+{
+    let mtv = object.mtv(fixed)?;
+    let normal = glm::normalize(mtv);
+    let (object_verts, object_radius) = object.outline();
+    let (fixed_verts, fixed_radius) = fixed.outline();
+    let object_edge = feature(&object_verts, normal * -1.)?;
+    let fixed_edge = feature(&fixed_verts, normal)?;
+
+    let object_incident = tilt(fixed_edge, normal) <= tilt(object_edge, normal);
+    let (incident, reference) = if object_incident {
+        (object_edge, fixed_edge)
+    } else {
+        (fixed_edge, object_edge)
+    };
+    // moves points out of the core to the side facing the other shape
+    let surface = |p: glm::DVec2, of_object: bool| {
+        if of_object {
+            p - normal * object_radius
+        } else {
+            p + normal * fixed_radius
+        }
+    };
+    let on_surface = |p| surface(p, object_incident);
+    let penetration = |p| {
+        let (object_point, fixed_point) = if object_incident {
+            (surface(p, true), surface(reference.0, false))
+        } else {
+            (surface(reference.0, true), surface(p, false))
+        };
+        glm::dot(fixed_point - object_point, normal)
+    };
+
+    let mut points: Vec<_> = clip(incident, reference)
+        .into_iter()
+        .filter(|&p| penetration(p) >= -EPSILON)
+        .map(on_surface)
+        .collect();
+    points.dedup_by(|a, b| glm::distance(*a, *b) <= EPSILON);
+    if points.is_empty() {
+        let deepest = if penetration(incident.0) >= penetration(incident.1) {
+            incident.0
+        } else {
+            incident.1
+        };
+        points.push(on_surface(deepest));
+    }
+
+    Some(Manifold {
+        normal,
+        depth: glm::length(mtv),
+        points,
+    })
+}
+
+/// Edge of `verts` furthest along `direction` that is flattest against it;
+/// both ends are the same point for shapes with a single vertex. There is no
+/// edge without any vertex that has a finite projection onto `direction`
+fn feature(verts: &[glm::DVec2], direction: glm::DVec2) -> Option<(glm::DVec2, glm::DVec2)> {
+    let count = verts.len();
+    let (index, &support) = verts
+        .iter()
+        .enumerate()
+        .filter(|(_, &v)| !glm::dot(v, direction).is_nan())
+        .max_by(|(_, &a), (_, &b)| glm::dot(a, direction).total_cmp(&glm::dot(b, direction)))?;
+    let previous = verts[(index + count - 1) % count];
+    let next = verts[(index + 1) % count];
+    let edge = if tilt((previous, support), direction) <= tilt((support, next), direction) {
+        (previous, support)
+    } else {
+        (support, next)
+    };
+    Some(edge)
+}
+
+/// How far from flat an edge is against `normal`, from 0 to 1; points
+/// count as upright since they have no flat side to rest on
+fn tilt(edge: (glm::DVec2, glm::DVec2), normal: glm::DVec2) -> f64 {
+    let along = edge.1 - edge.0;
+    if glm::dot(along, along) == 0. {
+        1.
+    } else {
+        glm::dot(glm::normalize(along), normal).abs()
+    }
+}
+
+/// Ends of `incident` that lie within the sides of `reference`
+fn clip(
+    incident: (glm::DVec2, glm::DVec2),
+    reference: (glm::DVec2, glm::DVec2),
+) -> Vec<glm::DVec2> {
+    let along = reference.1 - reference.0;
+    if glm::dot(along, along) == 0. {
+        return vec![incident.0, incident.1];
+    }
+    let side = glm::normalize(along);
+    let (low, high) = {
+        let (a, b) = (glm::dot(reference.0, side), glm::dot(reference.1, side));
+        (a.min(b), a.max(b))
+    };
+    let (start, end) = (glm::dot(incident.0, side), glm::dot(incident.1, side));
+    if start.max(end) < low || start.min(end) > high {
+        return vec![];
+    }
+    if start == end {
+        return vec![incident.0, incident.1];
+    }
+    let at = |t: f64| incident.0 + (incident.1 - incident.0) * ((t - start) / (end - start));
+    vec![at(start.max(low).min(high)), at(end.max(low).min(high))]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::Shape;
+
+    fn floor() -> Rectangle {
+        Rectangle {
+            top_left: glm::dvec2(0., 0.),
+            dims: glm::dvec2(10., 5.),
+        }
+    }
+
+    #[test]
+    fn no_feature_without_finite_projections() {
+        let square = [
+            glm::dvec2(0., 0.),
+            glm::dvec2(1., 0.),
+            glm::dvec2(1., 1.),
+            glm::dvec2(0., 1.),
+        ];
+        assert_eq!(feature(&[], glm::dvec2(0., 1.)), None);
+        assert_eq!(feature(&square, glm::dvec2(f64::NAN, 1.)), None);
+        assert_eq!(
+            feature(&square, glm::dvec2(0., 1.)),
+            Some((glm::dvec2(1., 1.), glm::dvec2(0., 1.)))
+        );
+    }
+
+    #[test]
+    fn capsule_lying_on_edge() {
+        let capsule = Capsule::new(glm::dvec2(2., -0.5), glm::dvec2(6., -0.5), 1.);
+        let manifold = capsule.manifold(&floor()).unwrap();
+        assert_eq!(manifold.normal, glm::dvec2(0., -1.));
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(
+            manifold.points,
+            vec![glm::dvec2(2., 0.5), glm::dvec2(6., 0.5)]
+        );
+    }
+
+    #[test]
+    fn capsule_standing_on_edge() {
+        let capsule = Capsule::vertical(glm::dvec2(3., -2.5), 6., 1.);
+        let manifold = capsule.manifold(&floor()).unwrap();
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(manifold.points, vec![glm::dvec2(3., 0.5)]);
+    }
+
+    #[test]
+    fn polygon_on_polygon_edge() {
+        let ramp = Polygon::new(vec![
+            glm::dvec2(0., 10.),
+            glm::dvec2(10., 0.),
+            glm::dvec2(10., 10.),
+        ])
+        .unwrap();
+        let block = OrientedRectangle::new(glm::dvec2(5., 4.), glm::dvec2(4., 2.), 315.);
+        let manifold = block.manifold(&ramp).unwrap();
+        let up = glm::dvec2(-1., -1.) / 2_f64.sqrt();
+        assert!(glm::distance(manifold.normal, up) < 0.00001);
+        assert!((manifold.depth - (1. - 1. / 2_f64.sqrt())).abs() < 0.00001);
+        assert_eq!(manifold.points.len(), 2);
+        for point in &manifold.points {
+            assert!(ramp.contains(point) || block.contains(point), "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn separate_shapes_have_no_manifold() {
+        let ball = Circle {
+            center: glm::dvec2(5., -2.),
+            radius: 1.,
+        };
+        assert_eq!(ball.manifold(&floor()), None);
+        assert_eq!(floor().manifold(&ball), None);
+    }
+
+    #[test]
+    fn clips_to_reference_sides() {
+        let incident = (glm::dvec2(-2., 1.), glm::dvec2(4., 1.));
+        let reference = (glm::dvec2(0., 0.), glm::dvec2(2., 0.));
+        assert_eq!(
+            clip(incident, reference),
+            vec![glm::dvec2(0., 1.), glm::dvec2(2., 1.)]
+        );
+        let beside = (glm::dvec2(3., 1.), glm::dvec2(4., 1.));
+        assert!(clip(beside, reference).is_empty());
+    }
+}
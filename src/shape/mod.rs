@@ -1,7 +1,33 @@
+pub mod broadphase;
+pub mod capsule;
 pub mod circle;
+#[macro_use]
+pub mod collider;
+pub mod manifold;
+pub mod oriented_rectangle;
+pub mod placement;
+pub mod polygon;
+pub mod ray;
 pub mod rectangle;
+pub mod segment;
+pub mod sweep;
 
-pub use self::{circle::Circle, rectangle::Rectangle};
+mod show;
+
+pub use self::{
+    broadphase::Grid,
+    capsule::Capsule,
+    circle::Circle,
+    collider::{Collider, Compound},
+    manifold::{Collide, Manifold},
+    oriented_rectangle::OrientedRectangle,
+    placement::Placement,
+    polygon::Polygon,
+    ray::{Hit, Ray, Raycast},
+    rectangle::Rectangle,
+    segment::Segment,
+    sweep::{Impact, Sweep},
+};
 
 pub type Line = (glm::DVec2, glm::DVec2);
 
@@ -11,10 +37,20 @@ pub trait Intersect<S> {
 }
 
 pub trait Shape {
+    /// What the shape becomes once rotated, e.g. rectangles become oriented
+    type Rotated: Shape;
+
     fn center(&self) -> glm::DVec2;
     fn contains(&self, point: &glm::DVec2) -> bool;
     fn nudge(self, nudge: glm::DVec2) -> Self;
     fn center_at(self, center: glm::DVec2) -> Self;
+    /// Smallest upright rectangle around the shape
+    fn aabb(&self) -> Rectangle;
+    /// Rotates clockwise by `angle` degrees around `pivot`, like
+    /// `options::Rotation`
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Self::Rotated;
+    /// Grows or shrinks around the center by `factor`
+    fn scale(self, factor: f64) -> Self;
 
     fn distance(&self, other: &impl Shape) -> f64 {
         glm::distance(self.center(), other.center())
@@ -40,47 +76,199 @@ impl Axis {
 
     fn mtv_range(&self, a: (f64, f64), b: (f64, f64)) -> Option<glm::DVec2> {
         let double_mag = (a.1 - a.0) + (b.1 - b.0) - ((a.1 + a.0) - (b.1 + b.0)).abs();
-        if double_mag <= 0. {
-            None
-        } else {
+        // also rejects the NaN of ranges without any vertices
+        if double_mag > 0. {
             let &Axis(axis) = self;
             let mag = double_mag / 2.;
             Some(axis * mag)
+        } else {
+            None
         }
     }
 
+    /// Range covered by `verts` along the axis; without vertices it is
+    /// empty so nothing overlaps it
     fn project(&self, verts: &[glm::DVec2]) -> (f64, f64) {
         let &Axis(axis) = self;
-        let min = verts
+        verts
             .iter()
             .map(|&v| glm::dot(axis, v))
-            .min_by(|x, y| x.partial_cmp(y).unwrap())
-            .unwrap();
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), d| {
+                (min.min(d), max.max(d))
+            })
+    }
+}
 
-        let max = verts
-            .iter()
-            .map(|v| glm::dot(axis, *v))
-            .max_by(|x, y| x.partial_cmp(y).unwrap())
-            .unwrap();
+/// Convex shapes that collide through the separating axis theorem, using
+/// the normals of their edges as the axes to test
+trait Convex: Shape {
+    fn vertices(&self) -> Vec<glm::DVec2>;
+    fn normals(&self) -> Vec<Axis>;
+}
+
+fn convex_mtv(object: &impl Convex, fixed: &impl Convex) -> Option<glm::DVec2> {
+    let (a, b) = (object.vertices(), fixed.vertices());
+    object
+        .normals()
+        .iter()
+        .chain(fixed.normals().iter())
+        .map(|axis| axis.mtv(&a, &b))
+        .find_mtv(object, fixed)
+}
+
+/// Moves `circle` out of `fixed`
+fn circle_mtv(circle: &Circle, fixed: &impl Convex) -> Option<glm::DVec2> {
+    let verts = fixed.vertices();
+    let closest = verts
+        .iter()
+        .map(|&v| v - circle.center)
+        .filter(|&v| glm::dot(v, v) > 0.)
+        .min_by(|&x, &y| glm::dot(x, x).total_cmp(&glm::dot(y, y)))
+        .map(|v| Axis(glm::normalize(v)));
+    fixed
+        .normals()
+        .iter()
+        .chain(closest.iter())
+        .map(|a| a.mtv_circle(&verts, circle))
+        .find_mtv(circle, fixed)
+}
+
+/// Moves `object` out of the segment `line`, endpoints included
+fn line_mtv(object: &impl Convex, line: &Line) -> Option<glm::DVec2> {
+    let verts = object.vertices();
+    let ends = [line.0, line.1];
+    let along = line.1 - line.0;
+    let normal = Some(glm::dvec2(-along.y, along.x))
+        .filter(|&n| glm::dot(n, n) > 0.)
+        .map(|n| Axis(glm::normalize(n)));
+    object
+        .normals()
+        .iter()
+        .chain(normal.iter())
+        .map(|a| a.mtv(&verts, &ends))
+        .find_mtv_from(object.center(), (line.0 + line.1) / 2.)
+}
+
+/// Moves `segment`, grown by `radius`, out of `fixed`; besides the usual
+/// normals, the axes from the segment to each vertex of `fixed` catch the
+/// round ends
+fn rounded_mtv(segment: &Segment, radius: f64, fixed: &impl Convex) -> Option<glm::DVec2> {
+    let verts = fixed.vertices();
+    let ends = [segment.start, segment.end];
+    let towards_verts = verts
+        .iter()
+        .map(|&v| v - segment.closest_point(v))
+        .filter(|&v| glm::dot(v, v) > 0.)
+        .map(|v| Axis(glm::normalize(v)));
+    fixed
+        .normals()
+        .into_iter()
+        .chain(segment.normal().map(Axis))
+        .chain(towards_verts)
+        .map(|axis| {
+            let (min, max) = axis.project(&ends);
+            axis.mtv_range((min - radius, max + radius), axis.project(&verts))
+        })
+        .find_mtv(segment, fixed)
+}
 
-        (min, max)
+/// Moves segment `a`, grown by `radius_a`, out of segment `b`, grown by
+/// `radius_b`; circles are segments with no length
+fn capsule_mtv(a: &Segment, radius_a: f64, b: &Segment, radius_b: f64) -> Option<glm::DVec2> {
+    let (on_a, on_b) = a.closest_points(b);
+    let gap = on_a - on_b;
+    let distance = glm::length(gap);
+    let reach = radius_a + radius_b;
+    if distance > 0. {
+        return if distance < reach {
+            Some(glm::ext::normalize_to(gap, reach - distance))
+        } else {
+            None
+        };
+    }
+
+    // the segments cross so the closest points give no direction
+    let (ends_a, ends_b) = ([a.start, a.end], [b.start, b.end]);
+    a.normal()
+        .into_iter()
+        .chain(b.normal())
+        .map(|normal| {
+            let axis = Axis(normal);
+            let (a_min, a_max) = axis.project(&ends_a);
+            let (b_min, b_max) = axis.project(&ends_b);
+            axis.mtv_range(
+                (a_min - radius_a, a_max + radius_a),
+                (b_min - radius_b, b_max + radius_b),
+            )
+        })
+        .find_mtv(a, b)
+}
+
+/// Rotates `point` clockwise around the origin, like `options::Rotation`
+pub(crate) fn rotate(point: glm::DVec2, degrees: f64) -> glm::DVec2 {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    glm::dvec2(point.x * cos - point.y * sin, point.x * sin + point.y * cos)
+}
+
+fn rotate_around(point: glm::DVec2, degrees: f64, pivot: glm::DVec2) -> glm::DVec2 {
+    pivot + rotate(point - pivot, degrees)
+}
+
+/// Smallest upright rectangle around `verts`
+fn bounds(verts: &[glm::DVec2]) -> Rectangle {
+    let (min, max) = verts.iter().fold(
+        (
+            glm::dvec2(f64::INFINITY, f64::INFINITY),
+            glm::dvec2(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), v| (glm::min(min, *v), glm::max(max, *v)),
+    );
+    Rectangle {
+        top_left: min,
+        dims: max - min,
     }
 }
 
-trait FindMtv {
-    fn find_mtv(self, object: &impl Shape, fixed: &impl Shape) -> Option<glm::DVec2>;
+/// Normals of the edges of a closed polygon
+fn edge_normals(verts: &[glm::DVec2]) -> impl Iterator<Item = Axis> + '_ {
+    verts
+        .iter()
+        .zip(verts.iter().cycle().skip(1))
+        .map(|(&a, &b)| b - a)
+        .filter(|&edge| glm::dot(edge, edge) > 0.)
+        .map(|edge| Axis(glm::normalize(glm::dvec2(-edge.y, edge.x))))
+}
+
+trait FindMtv: Sized {
+    fn find_mtv_from(self, object: glm::DVec2, fixed: glm::DVec2) -> Option<glm::DVec2>;
+
+    fn find_mtv(self, object: &impl Shape, fixed: &impl Shape) -> Option<glm::DVec2> {
+        self.find_mtv_from(object.center(), fixed.center())
+    }
 }
 
 impl<I> FindMtv for I
 where
     I: Iterator<Item = Option<glm::DVec2>>,
 {
-    fn find_mtv(self, object: &impl Shape, fixed: &impl Shape) -> Option<glm::DVec2> {
+    fn find_mtv_from(self, object: glm::DVec2, fixed: glm::DVec2) -> Option<glm::DVec2> {
         let mtvs: Vec<_> = self.collect::<Option<_>>()?;
         let min = *mtvs
             .iter()
             .min_by(|&&x, &&y| glm::length(x).partial_cmp(&glm::length(y)).unwrap())?;
-        let reversed = glm::dot(object.center() - fixed.center(), min) < 0.;
+        let reversed = glm::dot(object - fixed, min) < 0.;
         Some(if reversed { min * -1. } else { min })
     }
 }
+
+/// Checks a vector, or an mtv that has to be there, up to rounding errors
+#[cfg(test)]
+fn assert_near(actual: impl Into<Option<glm::DVec2>>, expected: glm::DVec2) {
+    let actual = actual.into().expect("expected an mtv");
+    assert!(
+        glm::distance(actual, expected) < 0.00001,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
@@ -0,0 +1,260 @@
+use super::{
+    bounds, circle_mtv, convex_mtv, line_mtv, rotate, rotate_around, Axis, Capsule, Circle, Convex,
+    Intersect, Line, Polygon, Rectangle, Segment, Shape,
+};
+
+/// Rectangle rotated clockwise around its center by `rotation` degrees
+#[derive(Debug, Clone, Copy)]
+pub struct OrientedRectangle {
+    pub center: glm::DVec2,
+    pub dims: glm::DVec2,
+    pub rotation: f64,
+}
+
+impl OrientedRectangle {
+    pub fn new(center: glm::DVec2, dims: glm::DVec2, rotation: f64) -> Self {
+        OrientedRectangle {
+            center,
+            dims,
+            rotation,
+        }
+    }
+
+    pub fn verts(&self) -> [glm::DVec2; 4] {
+        let half = self.dims / 2.;
+        [
+            glm::dvec2(-half.x, -half.y),
+            glm::dvec2(half.x, -half.y),
+            half,
+            glm::dvec2(-half.x, half.y),
+        ]
+        .map(|corner| self.center + rotate(corner, self.rotation))
+    }
+
+    pub fn axes(&self) -> [Axis; 2] {
+        [
+            Axis(rotate(glm::dvec2(0., 1.), self.rotation)),
+            Axis(rotate(glm::dvec2(1., 0.), self.rotation)),
+        ]
+    }
+}
+
+impl From<Rectangle> for OrientedRectangle {
+    fn from(rectangle: Rectangle) -> Self {
+        OrientedRectangle::new(rectangle.center(), rectangle.dims, 0.)
+    }
+}
+
+impl Convex for OrientedRectangle {
+    fn vertices(&self) -> Vec<glm::DVec2> {
+        self.verts().to_vec()
+    }
+
+    fn normals(&self) -> Vec<Axis> {
+        Vec::from(self.axes())
+    }
+}
+
+impl Shape for OrientedRectangle {
+    type Rotated = OrientedRectangle;
+
+    fn center(&self) -> glm::DVec2 {
+        self.center
+    }
+
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        let local = rotate(*point - self.center, -self.rotation);
+        let half = self.dims / 2.;
+        local.x.abs() <= half.x && local.y.abs() <= half.y
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> OrientedRectangle {
+        OrientedRectangle {
+            center: self.center + nudge,
+            ..self
+        }
+    }
+
+    fn center_at(self, center: glm::DVec2) -> OrientedRectangle {
+        OrientedRectangle { center, ..self }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        bounds(&self.verts())
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> OrientedRectangle {
+        OrientedRectangle {
+            center: rotate_around(self.center, angle, pivot),
+            rotation: self.rotation + angle,
+            ..self
+        }
+    }
+
+    fn scale(self, factor: f64) -> OrientedRectangle {
+        let dims = self.dims * factor;
+        OrientedRectangle { dims, ..self }
+    }
+}
+
+impl Intersect<OrientedRectangle> for OrientedRectangle {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Polygon> for OrientedRectangle {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Rectangle> for OrientedRectangle {
+    fn intersects(&self, other: &Rectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Rectangle) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Circle> for OrientedRectangle {
+    fn intersects(&self, other: &Circle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Circle) -> Option<glm::DVec2> {
+        circle_mtv(fixed, self).map(|v| v * -1.)
+    }
+}
+
+impl Intersect<Line> for OrientedRectangle {
+    fn intersects(&self, other: &Line) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Line) -> Option<glm::DVec2> {
+        line_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Segment> for OrientedRectangle {
+    fn intersects(&self, other: &Segment) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
+impl Intersect<Capsule> for OrientedRectangle {
+    fn intersects(&self, other: &Capsule) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::assert_near;
+
+    fn diamond() -> OrientedRectangle {
+        OrientedRectangle::new(glm::dvec2(0., 0.), glm::dvec2(2., 2.), 45.)
+    }
+
+    #[test]
+    fn oriented_contains() {
+        let diamond = diamond();
+        assert!(diamond.contains(&glm::dvec2(1.3, 0.)));
+        assert!(!diamond.contains(&glm::dvec2(0.9, 0.9)));
+    }
+
+    #[test]
+    fn unrotated_matches_rectangle() {
+        let rectangle = Rectangle {
+            top_left: glm::dvec2(2., -1.5),
+            dims: glm::dvec2(2., 3.),
+        };
+        let other = Rectangle {
+            top_left: glm::dvec2(2., 0.),
+            dims: glm::dvec2(1., 2.),
+        };
+        let oriented = OrientedRectangle::from(rectangle);
+        assert_eq!(oriented.verts(), rectangle.verts());
+        assert_near(oriented.mtv(&other), rectangle.mtv(&other).unwrap());
+        assert_near(other.mtv(&oriented), other.mtv(&rectangle).unwrap());
+    }
+
+    #[test]
+    fn oriented_rectangle_intersect() {
+        let diamond = diamond();
+        let rectangle = Rectangle {
+            top_left: glm::dvec2(1., -1.),
+            dims: glm::dvec2(2., 2.),
+        };
+        let depth = 2_f64.sqrt() - 1.;
+        assert!(diamond.intersects(&rectangle));
+        assert_near(diamond.mtv(&rectangle), glm::dvec2(-depth, 0.));
+        assert_near(rectangle.mtv(&diamond), glm::dvec2(depth, 0.));
+
+        let apart = rectangle.nudge(glm::dvec2(0.5, 0.));
+        assert!(!diamond.intersects(&apart));
+        assert_eq!(apart.mtv(&diamond), None);
+    }
+
+    #[test]
+    fn oriented_oriented_no_intersect() {
+        let a = diamond();
+        let b = diamond().nudge(glm::dvec2(2., 1.));
+        assert!(!a.intersects(&b));
+        assert_eq!(a.mtv(&b), None);
+    }
+
+    #[test]
+    fn oriented_oriented_intersect() {
+        let a = diamond();
+        let b = diamond().nudge(glm::dvec2(1., 1.));
+        assert!(a.intersects(&b));
+        let depth = 2_f64.sqrt() - 1.;
+        assert_near(a.mtv(&b), glm::dvec2(-depth, -depth));
+        assert_near(b.mtv(&a), glm::dvec2(depth, depth));
+    }
+
+    #[test]
+    fn oriented_circle_intersect() {
+        let diamond = diamond();
+        let circle = Circle {
+            center: glm::dvec2(2., 0.),
+            radius: 1.,
+        };
+        let depth = 2_f64.sqrt() - 1.;
+        assert!(diamond.intersects(&circle));
+        assert_near(circle.mtv(&diamond), glm::dvec2(depth, 0.));
+        assert_near(diamond.mtv(&circle), glm::dvec2(-depth, 0.));
+    }
+
+    #[test]
+    fn oriented_line_intersect() {
+        let diamond = diamond();
+        let line: Line = (glm::dvec2(-2., 1.), glm::dvec2(2., 1.));
+        assert!(diamond.intersects(&line));
+        assert_near(diamond.mtv(&line), glm::dvec2(0., 1. - 2_f64.sqrt()));
+
+        let beside: Line = (glm::dvec2(2., 1.), glm::dvec2(4., 1.));
+        assert!(!diamond.intersects(&beside));
+    }
+}
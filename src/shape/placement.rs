@@ -0,0 +1,174 @@
+use super::{rotate, Shape};
+use crate::renderer::{
+    options::{self, Rotation},
+    FDestination, Options,
+};
+
+/// Places things defined around the origin of their own local space in the
+/// world: scaled, then rotated clockwise by `rotation` degrees, then moved to
+/// `position`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub position: glm::DVec2,
+    pub rotation: f64,
+    pub scale: f64,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Placement::new(glm::dvec2(0., 0.))
+    }
+}
+
+impl Placement {
+    pub fn new(position: glm::DVec2) -> Self {
+        Placement {
+            position,
+            rotation: 0.,
+            scale: 1.,
+        }
+    }
+
+    pub fn rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Applies `child`, e.g. of a part relative to its body, and then this
+    pub fn then(&self, child: Placement) -> Placement {
+        Placement {
+            position: self.apply(child.position),
+            rotation: self.rotation + child.rotation,
+            scale: self.scale * child.scale,
+        }
+    }
+
+    /// Local point to world point
+    pub fn apply(&self, point: glm::DVec2) -> glm::DVec2 {
+        self.position + rotate(point * self.scale, self.rotation)
+    }
+
+    /// World point to local point
+    pub fn invert(&self, point: glm::DVec2) -> glm::DVec2 {
+        rotate(point - self.position, -self.rotation) / self.scale
+    }
+
+    pub fn to_world<S: Shape>(&self, shape: S) -> S::Rotated {
+        let center = shape.center();
+        let origin = glm::dvec2(0., 0.);
+        shape
+            .scale(self.scale)
+            .center_at(center * self.scale)
+            .rotate(self.rotation, origin)
+            .nudge(self.position)
+    }
+
+    pub fn to_local<S: Shape>(&self, shape: S) -> S::Rotated {
+        let origin = glm::dvec2(0., 0.);
+        let rotated = shape
+            .nudge(self.position * -1.)
+            .rotate(-self.rotation, origin);
+        let center = rotated.center();
+        rotated
+            .scale(1. / self.scale)
+            .center_at(center / self.scale)
+    }
+
+    /// Draws a texture `dims` big, centered on the local origin, so that it
+    /// lines up with shapes placed by `to_world`
+    pub fn options(&self, dims: glm::DVec2) -> Options {
+        let dims = dims * self.scale;
+        let dst = FDestination::new(self.position - dims / 2., dims);
+        options::at_precise(dst).rotate(Rotation {
+            angle: self.rotation,
+            center: glm::to_ivec2(glm::round(dims / 2.)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::assert_near;
+    use crate::shape::{Circle, Compound, OrientedRectangle, Polygon, Rectangle};
+
+    fn placement() -> Placement {
+        Placement::new(glm::dvec2(10., 5.)).rotation(90.).scale(2.)
+    }
+
+    #[test]
+    fn maps_points() {
+        let placement = placement();
+        let world = placement.apply(glm::dvec2(1., 0.));
+        assert_near(world, glm::dvec2(10., 7.));
+        assert_near(placement.invert(world), glm::dvec2(1., 0.));
+    }
+
+    #[test]
+    fn maps_shapes() {
+        let placement = placement();
+        let local = Rectangle {
+            top_left: glm::dvec2(1., -1.),
+            dims: glm::dvec2(4., 2.),
+        };
+        let world: OrientedRectangle = placement.to_world(local);
+        assert_near(world.center, glm::dvec2(10., 11.));
+        assert_near(world.dims, glm::dvec2(8., 4.));
+        assert_eq!(world.rotation, 90.);
+        assert!(world.contains(&glm::dvec2(11.5, 14.5)));
+
+        let back = placement.to_local(world);
+        assert_near(back.center, local.center());
+        assert_near(back.dims, local.dims);
+        assert_eq!(back.rotation, 0.);
+    }
+
+    #[test]
+    fn maps_compounds() {
+        let triangle = Polygon::new(vec![
+            glm::dvec2(0., -1.),
+            glm::dvec2(1., 1.),
+            glm::dvec2(-1., 1.),
+        ])
+        .unwrap();
+        let ball = Circle {
+            center: glm::dvec2(0., 0.),
+            radius: 1.,
+        };
+        let ship = Compound::new(glm::dvec2(0., 0.))
+            .part(glm::dvec2(0., 0.), triangle)
+            .part(glm::dvec2(3., 0.), ball);
+
+        let world = placement().to_world(ship);
+        assert_near(world.position, glm::dvec2(10., 5.));
+        assert!(world.contains(&glm::dvec2(10., 11.5)));
+        assert!(world.contains(&glm::dvec2(10., 5.)));
+        assert!(!world.contains(&glm::dvec2(16., 5.)));
+    }
+
+    #[test]
+    fn nests() {
+        let body = Placement::new(glm::dvec2(10., 0.)).rotation(90.);
+        let arm = Placement::new(glm::dvec2(2., 0.)).rotation(45.).scale(3.);
+        let point = glm::dvec2(1., 1.);
+        let both = body.then(arm);
+        assert_near(both.apply(point), body.apply(arm.apply(point)));
+        assert_eq!(both.rotation, 135.);
+    }
+
+    #[test]
+    fn lines_up_textures() {
+        let options = placement().options(glm::dvec2(4., 2.));
+        let dst = options.precise_dst().unwrap();
+        assert_near(dst.top_left, glm::dvec2(6., 3.));
+        assert_near(dst.dims, glm::dvec2(8., 4.));
+        let rotation = options.rotation.unwrap();
+        assert_eq!(rotation.angle, 90.);
+        assert_eq!(rotation.center, glm::ivec2(4, 2));
+    }
+}
@@ -0,0 +1,326 @@
+use super::{
+    bounds, circle_mtv, convex_mtv, edge_normals, line_mtv, rotate, rotate_around, Axis, Capsule,
+    Circle, Convex, Intersect, Line, OrientedRectangle, Rectangle, Segment, Shape,
+};
+use crate::Result;
+
+/// Convex polygon whose points are relative to its center and rotated
+/// clockwise around it by `rotation` degrees
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub center: glm::DVec2,
+    pub points: Vec<glm::DVec2>,
+    pub rotation: f64,
+}
+
+impl Polygon {
+    /// Polygon through `verts`, centered on their average; the vertices must
+    /// be in order around a convex outline and there must be at least three
+    pub fn new(verts: Vec<glm::DVec2>) -> Result<Self> {
+        if verts.len() < 3 {
+            return Err(failure::err_msg(format!(
+                "polygons need at least three vertices, got {}",
+                verts.len()
+            )));
+        }
+        let sum = verts.iter().fold(glm::dvec2(0., 0.), |sum, &v| sum + v);
+        let center = sum / verts.len() as f64;
+        Ok(Polygon {
+            center,
+            points: verts.into_iter().map(|v| v - center).collect(),
+            rotation: 0.,
+        })
+    }
+
+    pub fn verts(&self) -> Vec<glm::DVec2> {
+        self.points
+            .iter()
+            .map(|&p| self.center + rotate(p, self.rotation))
+            .collect()
+    }
+
+    pub fn axes(&self) -> Vec<Axis> {
+        edge_normals(&self.verts()).collect()
+    }
+}
+
+impl From<Rectangle> for Polygon {
+    fn from(rectangle: Rectangle) -> Self {
+        let center = rectangle.center();
+        Polygon {
+            center,
+            points: rectangle.verts().iter().map(|&v| v - center).collect(),
+            rotation: 0.,
+        }
+    }
+}
+
+impl From<OrientedRectangle> for Polygon {
+    fn from(rectangle: OrientedRectangle) -> Self {
+        let half = rectangle.dims / 2.;
+        Polygon {
+            center: rectangle.center,
+            points: vec![
+                glm::dvec2(-half.x, -half.y),
+                glm::dvec2(half.x, -half.y),
+                half,
+                glm::dvec2(-half.x, half.y),
+            ],
+            rotation: rectangle.rotation,
+        }
+    }
+}
+
+impl Convex for Polygon {
+    fn vertices(&self) -> Vec<glm::DVec2> {
+        self.verts()
+    }
+
+    fn normals(&self) -> Vec<Axis> {
+        self.axes()
+    }
+}
+
+impl Shape for Polygon {
+    type Rotated = Polygon;
+
+    fn center(&self) -> glm::DVec2 {
+        self.center
+    }
+
+    /// Polygons with fewer than three points have no inside
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        let verts = self.verts();
+        if verts.len() < 3 {
+            return false;
+        }
+        let sides: Vec<_> = verts
+            .iter()
+            .zip(verts.iter().cycle().skip(1))
+            .map(|(&a, &b)| {
+                let (edge, to_point) = (b - a, *point - a);
+                edge.x * to_point.y - edge.y * to_point.x
+            })
+            .collect();
+        sides.iter().all(|&s| s >= 0.) || sides.iter().all(|&s| s <= 0.)
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> Polygon {
+        Polygon {
+            center: self.center + nudge,
+            ..self
+        }
+    }
+
+    fn center_at(self, center: glm::DVec2) -> Polygon {
+        Polygon { center, ..self }
+    }
+
+    fn aabb(&self) -> Rectangle {
+        bounds(&self.verts())
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Polygon {
+        Polygon {
+            center: rotate_around(self.center, angle, pivot),
+            rotation: self.rotation + angle,
+            ..self
+        }
+    }
+
+    fn scale(self, factor: f64) -> Polygon {
+        let points = self.points.iter().map(|&p| p * factor).collect();
+        Polygon { points, ..self }
+    }
+}
+
+impl Intersect<Polygon> for Polygon {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<OrientedRectangle> for Polygon {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Rectangle> for Polygon {
+    fn intersects(&self, other: &Rectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Rectangle) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Circle> for Polygon {
+    fn intersects(&self, other: &Circle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Circle) -> Option<glm::DVec2> {
+        circle_mtv(fixed, self).map(|v| v * -1.)
+    }
+}
+
+impl Intersect<Line> for Polygon {
+    fn intersects(&self, other: &Line) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Line) -> Option<glm::DVec2> {
+        line_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Segment> for Polygon {
+    fn intersects(&self, other: &Segment) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
+impl Intersect<Capsule> for Polygon {
+    fn intersects(&self, other: &Capsule) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shape::assert_near;
+    use crate::shape::Collide;
+
+    fn triangle() -> Polygon {
+        Polygon::new(vec![
+            glm::dvec2(0., 0.),
+            glm::dvec2(6., 0.),
+            glm::dvec2(0., 6.),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn centers_on_average() {
+        let triangle = triangle();
+        assert_eq!(triangle.center, glm::dvec2(2., 2.));
+        assert_eq!(triangle.verts()[1], glm::dvec2(6., 0.));
+    }
+
+    #[test]
+    fn rejects_fewer_than_three_verts() {
+        assert!(Polygon::new(vec![glm::dvec2(0., 0.), glm::dvec2(6., 0.)]).is_err());
+    }
+
+    #[test]
+    fn empty_polygons_do_not_collide() {
+        let empty = Polygon {
+            points: vec![],
+            ..triangle()
+        };
+        assert!(!empty.contains(&triangle().center));
+        assert!(!empty.intersects(&triangle()));
+        assert_eq!(triangle().mtv(&empty), None);
+        assert_eq!(empty.manifold(&triangle()), None);
+    }
+
+    #[test]
+    fn polygon_contains() {
+        let triangle = triangle();
+        assert!(triangle.contains(&glm::dvec2(1., 1.)));
+        assert!(triangle.contains(&glm::dvec2(3., 3.)));
+        assert!(!triangle.contains(&glm::dvec2(4., 4.)));
+        assert!(!triangle.contains(&glm::dvec2(-1., 1.)));
+    }
+
+    #[test]
+    fn rotates_verts() {
+        let square = Polygon {
+            rotation: 90.,
+            ..Polygon::from(Rectangle {
+                top_left: glm::dvec2(0., 0.),
+                dims: glm::dvec2(4., 2.),
+            })
+        };
+        let verts = square.verts();
+        assert!(glm::distance(verts[0], glm::dvec2(3., -1.)) < 0.00001);
+        assert!(glm::distance(verts[2], glm::dvec2(1., 3.)) < 0.00001);
+    }
+
+    #[test]
+    fn polygon_polygon_no_intersect() {
+        let a = triangle();
+        let b = triangle().nudge(glm::dvec2(3.5, 3.5));
+        assert!(!a.intersects(&b));
+        assert_eq!(a.mtv(&b), None);
+        assert_eq!(b.mtv(&a), None);
+    }
+
+    #[test]
+    fn polygon_polygon_intersect() {
+        let a = triangle();
+        let b = triangle().nudge(glm::dvec2(2., 2.));
+        assert!(a.intersects(&b));
+        assert_near(a.mtv(&b), glm::dvec2(-1., -1.));
+        assert_near(b.mtv(&a), glm::dvec2(1., 1.));
+    }
+
+    #[test]
+    fn polygon_rectangle_intersect() {
+        let rectangle = Rectangle {
+            top_left: glm::dvec2(5., -2.),
+            dims: glm::dvec2(4., 4.),
+        };
+        let triangle = triangle();
+        assert!(triangle.intersects(&rectangle));
+        assert_near(triangle.mtv(&rectangle), glm::dvec2(-1., 0.));
+        assert_near(rectangle.mtv(&triangle), glm::dvec2(1., 0.));
+    }
+
+    #[test]
+    fn polygon_circle_intersect() {
+        let circle = Circle {
+            center: glm::dvec2(4., 4.),
+            radius: 2.,
+        };
+        let triangle = triangle();
+        let gap = 2. - 2_f64.sqrt();
+        assert!(triangle.intersects(&circle));
+        assert_near(triangle.mtv(&circle), glm::dvec2(-gap, -gap) / 2_f64.sqrt());
+        assert_near(circle.mtv(&triangle), glm::dvec2(gap, gap) / 2_f64.sqrt());
+
+        let far = circle.nudge(glm::dvec2(1., 1.));
+        assert!(!triangle.intersects(&far));
+        assert_eq!(far.mtv(&triangle), None);
+    }
+
+    #[test]
+    fn polygon_line_endpoints() {
+        let triangle = triangle();
+        let past_corner: Line = (glm::dvec2(7., -1.), glm::dvec2(7., 5.));
+        assert!(!triangle.intersects(&past_corner));
+
+        let crossing: Line = (glm::dvec2(-1., 1.), glm::dvec2(3., 1.));
+        assert!(triangle.intersects(&crossing));
+        assert_near(triangle.mtv(&crossing), glm::dvec2(0., 1.));
+    }
+}
@@ -0,0 +1,338 @@
+use super::{Capsule, Circle, Line, OrientedRectangle, Polygon, Rectangle, Segment, Shape};
+
+/// Half line from `origin`, cut off after `length`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: glm::DVec2,
+    /// Unit vector, or zero for a ray that hits nothing
+    pub direction: glm::DVec2,
+    pub length: f64,
+}
+
+impl Ray {
+    pub fn new(origin: glm::DVec2, direction: glm::DVec2) -> Self {
+        let direction = if glm::dot(direction, direction) > 0. {
+            glm::normalize(direction)
+        } else {
+            glm::dvec2(0., 0.)
+        };
+        Ray {
+            origin,
+            direction,
+            length: f64::INFINITY,
+        }
+    }
+
+    pub fn between(from: glm::DVec2, to: glm::DVec2) -> Self {
+        Ray::new(from, to - from).length(glm::distance(from, to))
+    }
+
+    pub fn length(mut self, length: f64) -> Self {
+        self.length = length;
+        self
+    }
+
+    pub fn at(&self, distance: f64) -> glm::DVec2 {
+        self.origin + self.direction * distance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hit {
+    pub point: glm::DVec2,
+    /// Unit normal of the surface that was hit, facing the ray
+    pub normal: glm::DVec2,
+    pub distance: f64,
+}
+
+/// Shapes that rays can hit; rays that start inside a shape do not hit it
+/// but rays that start on its surface and head in hit it right away
+pub trait Raycast {
+    fn raycast(&self, ray: &Ray) -> Option<Hit>;
+}
+
+impl Raycast for Circle {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        let from_center = ray.origin - self.center;
+        let b = glm::dot(from_center, ray.direction);
+        let c = glm::dot(from_center, from_center) - self.radius * self.radius;
+        let discriminant = b * b - c;
+        if c < 0. || b > 0. || discriminant < 0. {
+            return None;
+        }
+        let distance = -b - discriminant.sqrt();
+        if distance > ray.length {
+            return None;
+        }
+        let point = ray.at(distance);
+        Some(Hit {
+            point,
+            normal: glm::normalize(point - self.center),
+            distance,
+        })
+    }
+}
+
+impl Raycast for Segment {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        rounded_raycast(ray, &[self.start, self.end], 0.)
+    }
+}
+
+impl Raycast for Line {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        Segment::from(*self).raycast(ray)
+    }
+}
+
+impl Raycast for Rectangle {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        let verts = self.verts();
+        if inside(&verts, ray.origin) {
+            None
+        } else {
+            rounded_raycast(ray, &verts, 0.)
+        }
+    }
+}
+
+impl Raycast for OrientedRectangle {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        let verts = self.verts();
+        if inside(&verts, ray.origin) {
+            None
+        } else {
+            rounded_raycast(ray, &verts, 0.)
+        }
+    }
+}
+
+impl Raycast for Polygon {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        let verts = self.verts();
+        if inside(&verts, ray.origin) {
+            None
+        } else {
+            rounded_raycast(ray, &verts, 0.)
+        }
+    }
+}
+
+impl Raycast for Capsule {
+    fn raycast(&self, ray: &Ray) -> Option<Hit> {
+        if self.contains(&ray.origin) {
+            None
+        } else {
+            let ends = [self.segment.start, self.segment.end];
+            rounded_raycast(ray, &ends, self.radius)
+        }
+    }
+}
+
+/// Whether `point` is inside the convex outline through `verts` without
+/// being on any of its edges
+fn inside(verts: &[glm::DVec2], point: glm::DVec2) -> bool {
+    let sides: Vec<_> = verts
+        .iter()
+        .zip(verts.iter().cycle().skip(1))
+        .map(|(&a, &b)| {
+            let (edge, to_point) = (b - a, point - a);
+            edge.x * to_point.y - edge.y * to_point.x
+        })
+        .collect();
+    sides.iter().all(|&s| s > 0.) || sides.iter().all(|&s| s < 0.)
+}
+
+/// Casts `ray` at the convex outline through `verts` grown by `radius`,
+/// testing each edge pushed out along its normal and a circle around each
+/// vertex; only surfaces facing the ray are hit, so the origin must be
+/// outside the shape
+pub(super) fn rounded_raycast(ray: &Ray, verts: &[glm::DVec2], radius: f64) -> Option<Hit> {
+    let sum = verts.iter().fold(glm::dvec2(0., 0.), |sum, &v| sum + v);
+    let centroid = sum / verts.len().max(1) as f64;
+    let edges = verts
+        .iter()
+        .zip(verts.iter().cycle().skip(1))
+        .filter(|(a, b)| a != b)
+        .filter_map(|(&a, &b)| {
+            let edge = b - a;
+            let normal = glm::normalize(glm::dvec2(-edge.y, edge.x));
+            let normal = if glm::dot(normal, a - centroid) < 0. {
+                normal * -1.
+            } else {
+                normal
+            };
+            if glm::dot(normal, ray.direction) >= 0. {
+                return None;
+            }
+            let offset = normal * radius;
+            edge_hit(ray, a + offset, b + offset, normal)
+        });
+    let corners = verts.iter().filter(|_| radius > 0.).filter_map(|&center| {
+        let corner = Circle { center, radius };
+        corner.raycast(ray)
+    });
+    edges
+        .chain(corners)
+        .min_by(|a, b| a.distance.total_cmp(&b.distance))
+}
+
+fn edge_hit(ray: &Ray, start: glm::DVec2, end: glm::DVec2, normal: glm::DVec2) -> Option<Hit> {
+    let cross = |a: glm::DVec2, b: glm::DVec2| a.x * b.y - a.y * b.x;
+    let edge = end - start;
+    let denominator = cross(ray.direction, edge);
+    if denominator == 0. {
+        return None;
+    }
+    let to_start = start - ray.origin;
+    let distance = cross(to_start, edge) / denominator;
+    let along = cross(to_start, ray.direction) / denominator;
+    if distance < 0. || distance > ray.length || !(0. ..=1.).contains(&along) {
+        return None;
+    }
+    Some(Hit {
+        point: ray.at(distance),
+        normal,
+        distance,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_hit(hit: Option<Hit>, point: glm::DVec2, normal: glm::DVec2, distance: f64) {
+        let hit = hit.expect("expected a hit");
+        assert!(glm::distance(hit.point, point) < 0.00001, "{:?}", hit);
+        assert!(glm::distance(hit.normal, normal) < 0.00001, "{:?}", hit);
+        assert!((hit.distance - distance).abs() < 0.00001, "{:?}", hit);
+    }
+
+    #[test]
+    fn ray_circle() {
+        let circle = Circle {
+            radius: 2.,
+            center: glm::dvec2(10., 0.),
+        };
+        let ray = Ray::new(glm::dvec2(0., 0.), glm::dvec2(1., 0.));
+        assert_hit(
+            circle.raycast(&ray),
+            glm::dvec2(8., 0.),
+            glm::dvec2(-1., 0.),
+            8.,
+        );
+        assert_eq!(circle.raycast(&ray.length(7.)), None);
+
+        let away = Ray::new(glm::dvec2(0., 0.), glm::dvec2(-1., 0.));
+        assert_eq!(circle.raycast(&away), None);
+        let inside = Ray::new(glm::dvec2(10., 1.), glm::dvec2(1., 0.));
+        assert_eq!(circle.raycast(&inside), None);
+    }
+
+    #[test]
+    fn ray_rectangle() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 4.),
+            top_left: glm::dvec2(4., -1.),
+        };
+        let ray = Ray::between(glm::dvec2(0., 6.), glm::dvec2(6., 0.));
+        assert_hit(
+            rectangle.raycast(&ray),
+            glm::dvec2(4., 2.),
+            glm::dvec2(-1., 0.),
+            32_f64.sqrt(),
+        );
+
+        let from_below = Ray::new(glm::dvec2(5., 10.), glm::dvec2(0., -1.));
+        assert_hit(
+            rectangle.raycast(&from_below),
+            glm::dvec2(5., 3.),
+            glm::dvec2(0., 1.),
+            7.,
+        );
+
+        let past = Ray::new(glm::dvec2(0., 4.), glm::dvec2(1., 0.));
+        assert_eq!(rectangle.raycast(&past), None);
+    }
+
+    #[test]
+    fn ray_from_surface() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 4.),
+            top_left: glm::dvec2(4., -1.),
+        };
+        let origin = glm::dvec2(4., 1.);
+        let into = Ray::new(origin, glm::dvec2(1., 0.));
+        assert_hit(rectangle.raycast(&into), origin, glm::dvec2(-1., 0.), 0.);
+        let out = Ray::new(origin, glm::dvec2(-1., 0.));
+        assert_eq!(rectangle.raycast(&out), None);
+
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        let origin = glm::dvec2(-1., 1.);
+        let into = Ray::new(origin, glm::dvec2(1., 0.));
+        assert_hit(capsule.raycast(&into), origin, glm::dvec2(-1., 0.), 0.);
+    }
+
+    #[test]
+    fn ray_segment_from_both_sides() {
+        let line: Line = (glm::dvec2(0., -2.), glm::dvec2(0., 2.));
+        let right = Ray::new(glm::dvec2(-3., 1.), glm::dvec2(1., 0.));
+        assert_hit(
+            line.raycast(&right),
+            glm::dvec2(0., 1.),
+            glm::dvec2(-1., 0.),
+            3.,
+        );
+        let left = Ray::new(glm::dvec2(3., 1.), glm::dvec2(-1., 0.));
+        assert_hit(
+            line.raycast(&left),
+            glm::dvec2(0., 1.),
+            glm::dvec2(1., 0.),
+            3.,
+        );
+
+        let past_end = Ray::new(glm::dvec2(-3., 2.5), glm::dvec2(1., 0.));
+        assert_eq!(line.raycast(&past_end), None);
+        let parallel = Ray::new(glm::dvec2(0., -5.), glm::dvec2(0., 1.));
+        assert_eq!(line.raycast(&parallel), None);
+    }
+
+    #[test]
+    fn ray_polygon() {
+        let triangle = Polygon::new(vec![
+            glm::dvec2(0., 0.),
+            glm::dvec2(6., 0.),
+            glm::dvec2(0., 6.),
+        ])
+        .unwrap();
+        let ray = Ray::new(glm::dvec2(6., 6.), glm::dvec2(-1., -1.));
+        let diagonal = glm::dvec2(1., 1.) / 2_f64.sqrt();
+        assert_hit(
+            triangle.raycast(&ray),
+            glm::dvec2(3., 3.),
+            diagonal,
+            18_f64.sqrt(),
+        );
+        let inside = Ray::new(glm::dvec2(1., 1.), glm::dvec2(1., 0.));
+        assert_eq!(triangle.raycast(&inside), None);
+    }
+
+    #[test]
+    fn ray_capsule() {
+        let capsule = Capsule::vertical(glm::dvec2(0., 0.), 6., 1.);
+        let side = Ray::new(glm::dvec2(-5., 1.), glm::dvec2(1., 0.));
+        assert_hit(
+            capsule.raycast(&side),
+            glm::dvec2(-1., 1.),
+            glm::dvec2(-1., 0.),
+            4.,
+        );
+        let top = Ray::new(glm::dvec2(0., -10.), glm::dvec2(0., 1.));
+        assert_hit(
+            capsule.raycast(&top),
+            glm::dvec2(0., -3.),
+            glm::dvec2(0., -1.),
+            7.,
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Axis, Circle, FindMtv, Intersect, Line, Shape};
+use super::{
+    convex_mtv, Axis, Capsule, Circle, Convex, FindMtv, Intersect, Line, OrientedRectangle,
+    Polygon, Segment, Shape,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rectangle {
@@ -30,7 +33,19 @@ impl Rectangle {
     }
 }
 
+impl Convex for Rectangle {
+    fn vertices(&self) -> Vec<glm::DVec2> {
+        self.verts().to_vec()
+    }
+
+    fn normals(&self) -> Vec<Axis> {
+        Vec::from(self.axes())
+    }
+}
+
 impl Shape for Rectangle {
+    type Rotated = OrientedRectangle;
+
     fn center(&self) -> glm::DVec2 {
         self.top_left + self.dims / 2.
     }
@@ -55,6 +70,19 @@ impl Shape for Rectangle {
             ..self
         }
     }
+
+    fn aabb(&self) -> Rectangle {
+        *self
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> OrientedRectangle {
+        OrientedRectangle::from(self).rotate(angle, pivot)
+    }
+
+    fn scale(self, factor: f64) -> Rectangle {
+        let dims = self.dims * factor;
+        Rectangle { dims, ..self }.center_at(self.center())
+    }
 }
 
 impl Intersect<Rectangle> for Rectangle {
@@ -86,9 +114,50 @@ impl Intersect<Circle> for Rectangle {
     }
 }
 
+impl Intersect<OrientedRectangle> for Rectangle {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Polygon> for Rectangle {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        convex_mtv(self, fixed)
+    }
+}
+
+impl Intersect<Segment> for Rectangle {
+    fn intersects(&self, other: &Segment) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
+impl Intersect<Capsule> for Rectangle {
+    fn intersects(&self, other: &Capsule) -> bool {
+        other.intersects(self)
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        fixed.mtv(self).map(|v| v * -1.)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::shape::Collide;
 
     #[test]
     fn rectangle_no_contains() {
@@ -232,4 +301,72 @@ mod test {
         assert_eq!(nudged.dims, glm::dvec2(2., 2.));
         assert_eq!(nudged.center(), glm::dvec2(-1.5, 0.7));
     }
+
+    #[test]
+    fn rotate() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(4., 2.),
+        };
+        let rotated = rectangle.rotate(90., glm::dvec2(5., 1.));
+        assert!(glm::distance(rotated.center, glm::dvec2(3., 1.)) < 0.00001);
+        assert_eq!(rotated.dims, rectangle.dims);
+        assert_eq!(rotated.rotation, 90.);
+    }
+
+    #[test]
+    fn scale() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(4., 2.),
+        };
+        let scaled = rectangle.scale(2.);
+        assert_eq!(scaled.dims, glm::dvec2(4., 4.));
+        assert_eq!(scaled.top_left, glm::dvec2(3., 1.));
+    }
+
+    fn floor() -> Rectangle {
+        Rectangle {
+            dims: glm::dvec2(10., 5.),
+            top_left: glm::dvec2(0., 0.),
+        }
+    }
+
+    #[test]
+    fn rectangle_edge_manifold() {
+        let block = Rectangle {
+            dims: glm::dvec2(4., 2.),
+            top_left: glm::dvec2(2., -1.5),
+        };
+        let manifold = block.manifold(&floor()).unwrap();
+        assert_eq!(manifold.normal, glm::dvec2(0., -1.));
+        assert_eq!(manifold.depth, 0.5);
+        assert_eq!(manifold.points.len(), 2);
+        assert!(manifold.points.contains(&glm::dvec2(2., 0.5)));
+        assert!(manifold.points.contains(&glm::dvec2(6., 0.5)));
+
+        let overhanging = block.nudge(glm::dvec2(6., 0.));
+        let manifold = overhanging.manifold(&floor()).unwrap();
+        assert_eq!(manifold.points.len(), 2);
+        assert!(manifold.points.contains(&glm::dvec2(8., 0.5)));
+        assert!(manifold.points.contains(&glm::dvec2(10., 0.5)));
+    }
+
+    #[test]
+    fn rectangle_corner_manifold() {
+        let diamond = OrientedRectangle::new(glm::dvec2(5., -1.2), glm::dvec2(2., 2.), 45.);
+        let depth = 2_f64.sqrt() - 1.2;
+        let corner = glm::dvec2(5., depth);
+
+        let manifold = diamond.manifold(&floor()).unwrap();
+        assert!(glm::distance(manifold.normal, glm::dvec2(0., -1.)) < 0.00001);
+        assert!((manifold.depth - depth).abs() < 0.00001);
+        assert_eq!(manifold.points.len(), 1);
+        assert!(glm::distance(manifold.points[0], corner) < 0.00001);
+
+        let manifold = floor().manifold(&diamond).unwrap();
+        assert!(glm::distance(manifold.normal, glm::dvec2(0., 1.)) < 0.00001);
+        assert_eq!(manifold.points.len(), 1);
+        assert!(glm::distance(manifold.points[0], corner) < 0.00001);
+    }
 }
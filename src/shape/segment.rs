@@ -0,0 +1,297 @@
+use super::{
+    bounds, capsule_mtv, rotate_around, rounded_mtv, Capsule, Circle, Intersect, Line,
+    OrientedRectangle, Polygon, Rectangle, Shape,
+};
+
+/// Distance under which a point counts as lying on a segment
+const ON_SEGMENT: f64 = 1e-9;
+
+/// Straight line between two points, colliding only between its endpoints
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    pub start: glm::DVec2,
+    pub end: glm::DVec2,
+}
+
+impl Segment {
+    pub fn new(start: glm::DVec2, end: glm::DVec2) -> Self {
+        Segment { start, end }
+    }
+
+    pub fn length(&self) -> f64 {
+        glm::distance(self.start, self.end)
+    }
+
+    /// Unit normal, or `None` if the segment has no length
+    pub fn normal(&self) -> Option<glm::DVec2> {
+        let along = self.end - self.start;
+        if glm::dot(along, along) > 0. {
+            Some(glm::normalize(glm::dvec2(-along.y, along.x)))
+        } else {
+            None
+        }
+    }
+
+    pub fn closest_point(&self, point: glm::DVec2) -> glm::DVec2 {
+        let along = self.end - self.start;
+        let len_sq = glm::dot(along, along);
+        if len_sq == 0. {
+            return self.start;
+        }
+        let t = glm::dot(point - self.start, along) / len_sq;
+        self.start + along * t.clamp(0., 1.)
+    }
+
+    /// Closest points on `self` and `other`, in that order
+    pub fn closest_points(&self, other: &Segment) -> (glm::DVec2, glm::DVec2) {
+        if let Some(crossing) = self.crossing(other) {
+            return (crossing, crossing);
+        }
+        let candidates = [
+            (self.start, other.closest_point(self.start)),
+            (self.end, other.closest_point(self.end)),
+            (self.closest_point(other.start), other.start),
+            (self.closest_point(other.end), other.end),
+        ];
+        *candidates
+            .iter()
+            .min_by(|(a, b), (c, d)| glm::distance(*a, *b).total_cmp(&glm::distance(*c, *d)))
+            .unwrap()
+    }
+
+    /// Point where two non-parallel segments cross
+    pub fn crossing(&self, other: &Segment) -> Option<glm::DVec2> {
+        let cross = |a: glm::DVec2, b: glm::DVec2| a.x * b.y - a.y * b.x;
+        let (r, s) = (self.end - self.start, other.end - other.start);
+        let denominator = cross(r, s);
+        if denominator == 0. {
+            return None;
+        }
+        let offset = other.start - self.start;
+        let t = cross(offset, s) / denominator;
+        let u = cross(offset, r) / denominator;
+        if (0. ..=1.).contains(&t) && (0. ..=1.).contains(&u) {
+            Some(self.start + r * t)
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Line> for Segment {
+    fn from((start, end): Line) -> Self {
+        Segment::new(start, end)
+    }
+}
+
+impl From<Segment> for Line {
+    fn from(segment: Segment) -> Self {
+        (segment.start, segment.end)
+    }
+}
+
+impl Shape for Segment {
+    type Rotated = Segment;
+
+    fn center(&self) -> glm::DVec2 {
+        (self.start + self.end) / 2.
+    }
+
+    fn contains(&self, point: &glm::DVec2) -> bool {
+        glm::distance(self.closest_point(*point), *point) <= ON_SEGMENT
+    }
+
+    fn nudge(self, nudge: glm::DVec2) -> Segment {
+        Segment::new(self.start + nudge, self.end + nudge)
+    }
+
+    fn center_at(self, center: glm::DVec2) -> Segment {
+        let nudge = center - self.center();
+        self.nudge(nudge)
+    }
+
+    fn aabb(&self) -> Rectangle {
+        bounds(&[self.start, self.end])
+    }
+
+    fn rotate(self, angle: f64, pivot: glm::DVec2) -> Segment {
+        Segment::new(
+            rotate_around(self.start, angle, pivot),
+            rotate_around(self.end, angle, pivot),
+        )
+    }
+
+    fn scale(self, factor: f64) -> Segment {
+        let center = self.center();
+        Segment::new(
+            center + (self.start - center) * factor,
+            center + (self.end - center) * factor,
+        )
+    }
+}
+
+impl Intersect<Segment> for Segment {
+    fn intersects(&self, other: &Segment) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Segment) -> Option<glm::DVec2> {
+        capsule_mtv(self, 0., fixed, 0.)
+    }
+}
+
+impl Intersect<Capsule> for Segment {
+    fn intersects(&self, other: &Capsule) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Capsule) -> Option<glm::DVec2> {
+        capsule_mtv(self, 0., &fixed.segment, fixed.radius)
+    }
+}
+
+impl Intersect<Circle> for Segment {
+    fn intersects(&self, other: &Circle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Circle) -> Option<glm::DVec2> {
+        let center = Segment::new(fixed.center, fixed.center);
+        capsule_mtv(self, 0., &center, fixed.radius)
+    }
+}
+
+impl Intersect<Rectangle> for Segment {
+    fn intersects(&self, other: &Rectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Rectangle) -> Option<glm::DVec2> {
+        rounded_mtv(self, 0., fixed)
+    }
+}
+
+impl Intersect<OrientedRectangle> for Segment {
+    fn intersects(&self, other: &OrientedRectangle) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &OrientedRectangle) -> Option<glm::DVec2> {
+        rounded_mtv(self, 0., fixed)
+    }
+}
+
+impl Intersect<Polygon> for Segment {
+    fn intersects(&self, other: &Polygon) -> bool {
+        self.mtv(other).is_some()
+    }
+
+    fn mtv(&self, fixed: &Polygon) -> Option<glm::DVec2> {
+        rounded_mtv(self, 0., fixed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn segment_contains() {
+        let segment = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 2.));
+        assert!(segment.contains(&glm::dvec2(2., 1.)));
+        assert!(!segment.contains(&glm::dvec2(6., 3.)));
+        assert!(!segment.contains(&glm::dvec2(2., 1.5)));
+    }
+
+    #[test]
+    fn closest_point_clamps_to_endpoints() {
+        let segment = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.));
+        assert_eq!(
+            segment.closest_point(glm::dvec2(2., 3.)),
+            glm::dvec2(2., 0.)
+        );
+        assert_eq!(
+            segment.closest_point(glm::dvec2(-2., 3.)),
+            glm::dvec2(0., 0.)
+        );
+        assert_eq!(
+            segment.closest_point(glm::dvec2(7., -1.)),
+            glm::dvec2(4., 0.)
+        );
+    }
+
+    #[test]
+    fn segment_segment_no_intersect() {
+        let a = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.));
+        let b = Segment::new(glm::dvec2(5., -2.), glm::dvec2(5., 2.));
+        assert_eq!(a.crossing(&b), None);
+        assert!(!a.intersects(&b));
+        assert_eq!(a.mtv(&b), None);
+        assert_eq!(
+            a.closest_points(&b),
+            (glm::dvec2(4., 0.), glm::dvec2(5., 0.))
+        );
+    }
+
+    #[test]
+    fn segment_segment_intersect() {
+        let a = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.));
+        let b = Segment::new(glm::dvec2(3., -2.), glm::dvec2(3., 0.5));
+        assert_eq!(a.crossing(&b), Some(glm::dvec2(3., 0.)));
+        assert!(a.intersects(&b));
+        assert_eq!(a.mtv(&b), Some(glm::dvec2(0., 0.5)));
+        assert_eq!(b.mtv(&a), Some(glm::dvec2(0., -0.5)));
+    }
+
+    #[test]
+    fn segment_circle_past_endpoint() {
+        let segment = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 0.));
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(5.5, 0.5),
+        };
+        assert!(!segment.intersects(&circle));
+        assert_eq!(circle.mtv(&segment), None);
+
+        let circle = circle.nudge(glm::dvec2(-1., -0.5));
+        assert!(segment.intersects(&circle));
+        assert_eq!(circle.mtv(&segment), Some(glm::dvec2(0.5, 0.)));
+        assert_eq!(segment.mtv(&circle), Some(glm::dvec2(-0.5, 0.)));
+    }
+
+    #[test]
+    fn segment_rectangle_intersect() {
+        let rectangle = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(4., 2.),
+        };
+        let segment = Segment::new(glm::dvec2(0., 2.5), glm::dvec2(4.8, 2.5));
+        assert!(segment.intersects(&rectangle));
+        assert_eq!(segment.mtv(&rectangle), Some(glm::dvec2(0., -0.5)));
+        assert_eq!(rectangle.mtv(&segment), Some(glm::dvec2(0., 0.5)));
+
+        let beside = segment.nudge(glm::dvec2(-1., 0.));
+        assert!(!beside.intersects(&rectangle));
+        assert_eq!(rectangle.mtv(&beside), None);
+    }
+
+    #[test]
+    fn segment_oriented_rectangle_intersect() {
+        let diamond = OrientedRectangle::new(glm::dvec2(0., 0.), glm::dvec2(2., 2.), 45.);
+        let segment = Segment::new(glm::dvec2(-3., -1.2), glm::dvec2(3., -1.2));
+        let depth = 2_f64.sqrt() - 1.2;
+        let mtv = segment.mtv(&diamond).expect("expected an mtv");
+        assert!(glm::distance(mtv, glm::dvec2(0., -depth)) < 0.00001);
+        let mtv = diamond.mtv(&segment).expect("expected an mtv");
+        assert!(glm::distance(mtv, glm::dvec2(0., depth)) < 0.00001);
+        assert!(!segment.nudge(glm::dvec2(0., -0.3)).intersects(&diamond));
+    }
+
+    #[test]
+    fn center_at() {
+        let segment = Segment::new(glm::dvec2(0., 0.), glm::dvec2(4., 2.));
+        let moved = segment.center_at(glm::dvec2(-1.5, 0.7));
+        assert_eq!(moved.center(), glm::dvec2(-1.5, 0.7));
+        assert_eq!(moved.end - moved.start, glm::dvec2(4., 2.));
+    }
+}
@@ -0,0 +1,135 @@
+//! Outlines shapes with the current draw color, for debugging collisions
+
+use super::{
+    Capsule, Circle, Collider, Compound, Line, OrientedRectangle, Polygon, Rectangle, Segment,
+    Shape,
+};
+use crate::{
+    renderer::{FDestination, Renderer, Show},
+    Result,
+};
+
+impl<R: Renderer> Show<R> for Circle {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        let center = glm::to_ivec2(glm::round(self.center));
+        renderer.draw_circle(center, self.radius.round() as u32)
+    }
+}
+
+impl<R: Renderer> Show<R> for Rectangle {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        let dst = FDestination::new(self.top_left, self.dims).round();
+        renderer.draw_rects(&[dst])
+    }
+}
+
+impl<R: Renderer> Show<R> for Line {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        let (start, end) = *self;
+        let points = [start, end].map(|p| glm::to_ivec2(glm::round(p)));
+        renderer.draw_lines(&points)
+    }
+}
+
+impl<R: Renderer> Show<R> for Segment {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.show(&Line::from(*self))
+    }
+}
+
+/// Outlines the sides and both round ends in full
+impl<R: Renderer> Show<R> for Capsule {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        let side = self.segment.normal().unwrap_or(glm::dvec2(0., 0.)) * self.radius;
+        renderer.show(&self.segment.nudge(side))?;
+        renderer.show(&self.segment.nudge(side * -1.))?;
+        [self.segment.start, self.segment.end]
+            .iter()
+            .map(|&center| Circle {
+                center,
+                radius: self.radius,
+            })
+            .try_for_each(|end| renderer.show(&end))
+    }
+}
+
+impl<R: Renderer> Show<R> for OrientedRectangle {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        outline(&self.verts(), renderer)
+    }
+}
+
+impl<R: Renderer> Show<R> for Polygon {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        outline(&self.verts(), renderer)
+    }
+}
+
+impl<R: Renderer> Show<R> for Compound {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        self.shapes().try_for_each(|shape| renderer.show(&shape))
+    }
+}
+
+impl<R: Renderer> Show<R> for Collider {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        dispatch!(self, shape => renderer.show(shape))
+    }
+}
+
+fn outline(verts: &[glm::DVec2], renderer: &mut impl Renderer) -> Result<()> {
+    let points: Vec<_> = verts
+        .iter()
+        .chain(verts.first())
+        .map(|&p| glm::to_ivec2(glm::round(p)))
+        .collect();
+    renderer.draw_lines(&points)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::renderer::{align, mocks::MockCanvas};
+
+    #[test]
+    fn shows_shapes() {
+        let mut canvas = MockCanvas::new();
+        let rectangle = Rectangle {
+            top_left: glm::dvec2(1.4, 2.6),
+            dims: glm::dvec2(10., 5.),
+        };
+        let line: Line = (glm::dvec2(0., 0.), glm::dvec2(3.6, 4.2));
+        let circle = Circle {
+            center: glm::dvec2(20., 20.),
+            radius: 4.,
+        };
+
+        assert!(canvas.show(&rectangle).is_ok());
+        assert!(canvas.show(&line).is_ok());
+        assert!(canvas.show(&circle).is_ok());
+
+        let expected = align::left(1).top(3).dims(glm::uvec2(10, 5));
+        assert_eq!(canvas.outline, vec![expected]);
+        assert_eq!(canvas.lines[0], vec![glm::ivec2(0, 0), glm::ivec2(4, 4)]);
+        assert_eq!(canvas.lines[1][0], glm::ivec2(24, 20));
+    }
+
+    #[test]
+    fn closes_polygons() {
+        let mut canvas = MockCanvas::new();
+        let polygon = Polygon::new(vec![
+            glm::dvec2(0., 0.),
+            glm::dvec2(4., 0.),
+            glm::dvec2(0., 3.),
+        ])
+        .unwrap();
+        assert!(canvas.show(&polygon).is_ok());
+        let expected = vec![
+            glm::ivec2(0, 0),
+            glm::ivec2(4, 0),
+            glm::ivec2(0, 3),
+            glm::ivec2(0, 0),
+        ];
+        assert_eq!(canvas.lines, vec![expected]);
+    }
+}
@@ -0,0 +1,358 @@
+use super::{
+    ray::{rounded_raycast, Ray},
+    Capsule, Circle, Intersect, OrientedRectangle, Polygon, Rectangle, Segment, Shape,
+};
+
+/// When and against which surface a moving shape first touches another
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Impact {
+    /// Fraction of the motion, from 0 to 1, covered before touching
+    pub time: f64,
+    /// Unit normal of the surface that was hit, facing the moving shape
+    pub normal: glm::DVec2,
+}
+
+/// Continuous collision of a shape moving by `motion` against a fixed one,
+/// so that fast shapes cannot pass through thin ones between updates;
+/// shapes that already overlap hit at time 0, along their mtv
+pub trait Sweep<S> {
+    fn sweep(&self, motion: glm::DVec2, fixed: &S) -> Option<Impact>;
+}
+
+/// Convex outline of a shape as its vertices grown by a radius
+pub(super) trait Outline {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64);
+}
+
+impl Outline for Circle {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (vec![self.center], self.radius)
+    }
+}
+
+impl Outline for Rectangle {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (self.verts().to_vec(), 0.)
+    }
+}
+
+impl Outline for OrientedRectangle {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (self.verts().to_vec(), 0.)
+    }
+}
+
+impl Outline for Polygon {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (self.verts(), 0.)
+    }
+}
+
+impl Outline for Segment {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (vec![self.start, self.end], 0.)
+    }
+}
+
+impl Outline for Capsule {
+    fn outline(&self) -> (Vec<glm::DVec2>, f64) {
+        (vec![self.segment.start, self.segment.end], self.radius)
+    }
+}
+
+/// Casts the center of `moving` at `fixed` grown by the shape of `moving`,
+/// i.e. their Minkowski difference
+fn sweep<M, F>(moving: &M, motion: glm::DVec2, fixed: &F) -> Option<Impact>
+where
+    M: Outline + Shape + Intersect<F>,
+    F: Outline,
+{
+    if let Some(mtv) = moving.mtv(fixed) {
+        return Some(Impact {
+            time: 0.,
+            normal: glm::normalize(mtv),
+        });
+    }
+    let distance = glm::length(motion);
+    if distance == 0. {
+        return None;
+    }
+
+    let center = moving.center();
+    let (moving_verts, moving_radius) = moving.outline();
+    let (fixed_verts, fixed_radius) = fixed.outline();
+    let grown = hull(
+        fixed_verts
+            .iter()
+            .flat_map(|&f| moving_verts.iter().map(move |&m| f - (m - center)))
+            .collect(),
+    );
+    let ray = Ray::new(center, motion).length(distance);
+    rounded_raycast(&ray, &grown, moving_radius + fixed_radius).map(|hit| Impact {
+        time: hit.distance / distance,
+        normal: hit.normal,
+    })
+}
+
+/// Convex hull in counter clockwise order, by Andrew's monotone chain
+fn hull(mut points: Vec<glm::DVec2>) -> Vec<glm::DVec2> {
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+
+    let cross = |o: glm::DVec2, a: glm::DVec2, b: glm::DVec2| {
+        (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+    };
+    let mut hull: Vec<glm::DVec2> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        let mut add = |p: glm::DVec2| {
+            while hull.len() >= start + 2
+                && cross(hull[hull.len() - 2], hull[hull.len() - 1], p) <= 0.
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        };
+        if pass == 0 {
+            points.iter().for_each(|&p| add(p));
+        } else {
+            points.iter().rev().for_each(|&p| add(p));
+        }
+        hull.pop();
+    }
+    hull
+}
+
+impl Sweep<Circle> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Circle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Rectangle> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Rectangle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<OrientedRectangle> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &OrientedRectangle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Polygon> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Polygon) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Segment> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Segment) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Capsule> for Circle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Capsule) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Circle> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Circle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Rectangle> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Rectangle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<OrientedRectangle> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &OrientedRectangle) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Polygon> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Polygon) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Segment> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Segment) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+impl Sweep<Capsule> for Rectangle {
+    fn sweep(&self, motion: glm::DVec2, fixed: &Capsule) -> Option<Impact> {
+        sweep(self, motion, fixed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_impact(impact: Option<Impact>, time: f64, normal: glm::DVec2) {
+        let impact = impact.expect("expected an impact");
+        assert!((impact.time - time).abs() < 0.00001, "{:?}", impact);
+        assert!(
+            glm::distance(impact.normal, normal) < 0.00001,
+            "{:?}",
+            impact
+        );
+    }
+
+    fn wall() -> Rectangle {
+        Rectangle {
+            dims: glm::dvec2(1., 10.),
+            top_left: glm::dvec2(10., -5.),
+        }
+    }
+
+    #[test]
+    fn bullet_stops_at_wall() {
+        let bullet = Circle {
+            radius: 0.5,
+            center: glm::dvec2(0., 0.),
+        };
+        let motion = glm::dvec2(100., 0.);
+        assert!(!bullet.nudge(motion).intersects(&wall()));
+        assert_impact(bullet.sweep(motion, &wall()), 0.095, glm::dvec2(-1., 0.));
+    }
+
+    #[test]
+    fn circle_misses() {
+        let circle = Circle {
+            radius: 0.5,
+            center: glm::dvec2(0., 6.),
+        };
+        assert_eq!(circle.sweep(glm::dvec2(100., 0.), &wall()), None);
+        assert_eq!(circle.sweep(glm::dvec2(-100., -6.), &wall()), None);
+        assert_eq!(circle.sweep(glm::dvec2(5., 0.), &wall()), None);
+    }
+
+    #[test]
+    fn circle_hits_corner() {
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(0., -5.6),
+        };
+        let impact = circle.sweep(glm::dvec2(20., 0.), &wall());
+        assert_impact(impact, 0.46, glm::dvec2(-0.8, -0.6));
+    }
+
+    #[test]
+    fn circle_circle() {
+        let a = Circle {
+            radius: 1.,
+            center: glm::dvec2(0., 0.),
+        };
+        let b = Circle {
+            radius: 2.,
+            center: glm::dvec2(0., 10.),
+        };
+        assert_impact(a.sweep(glm::dvec2(0., 14.), &b), 0.5, glm::dvec2(0., -1.));
+    }
+
+    #[test]
+    fn circle_segment() {
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(0., 0.),
+        };
+        let segment = Segment::new(glm::dvec2(5., -1.), glm::dvec2(5., 1.));
+        assert_impact(
+            circle.sweep(glm::dvec2(8., 0.), &segment),
+            0.5,
+            glm::dvec2(-1., 0.),
+        );
+    }
+
+    #[test]
+    fn rectangle_rectangle() {
+        let player = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(0., 0.),
+        };
+        let impact = player.sweep(glm::dvec2(16., 8.), &wall());
+        assert_impact(impact, 0.5, glm::dvec2(-1., 0.));
+
+        let above = player.nudge(glm::dvec2(0., -10.));
+        assert_eq!(above.sweep(glm::dvec2(16., 0.), &wall()), None);
+    }
+
+    #[test]
+    fn rectangle_circle() {
+        let player = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(0., 0.),
+        };
+        let ball = Circle {
+            radius: 1.,
+            center: glm::dvec2(1., 10.),
+        };
+        let impact = player.sweep(glm::dvec2(0., 14.), &ball);
+        assert_impact(impact, 0.5, glm::dvec2(0., -1.));
+    }
+
+    #[test]
+    fn rectangle_polygon() {
+        let player = Rectangle {
+            dims: glm::dvec2(2., 2.),
+            top_left: glm::dvec2(-1., -1.),
+        };
+        let ramp = Polygon::new(vec![
+            glm::dvec2(10., 10.),
+            glm::dvec2(20., 0.),
+            glm::dvec2(20., 10.),
+        ])
+        .unwrap();
+        let impact = player.sweep(glm::dvec2(20., 0.), &ramp);
+        let normal = glm::dvec2(-1., -1.) / 2_f64.sqrt();
+        assert_impact(impact, 0.9, normal);
+    }
+
+    #[test]
+    fn overlapping_hits_at_start() {
+        let circle = Circle {
+            radius: 1.,
+            center: glm::dvec2(10., 0.),
+        };
+        assert_impact(
+            circle.sweep(glm::dvec2(5., 0.), &wall()),
+            0.,
+            glm::dvec2(-1., 0.),
+        );
+    }
+
+    #[test]
+    fn hulls_points() {
+        let points = vec![
+            glm::dvec2(0., 0.),
+            glm::dvec2(2., 2.),
+            glm::dvec2(1., 1.),
+            glm::dvec2(2., 0.),
+            glm::dvec2(0., 2.),
+            glm::dvec2(0., 2.),
+        ];
+        assert_eq!(
+            hull(points),
+            vec![
+                glm::dvec2(0., 0.),
+                glm::dvec2(2., 0.),
+                glm::dvec2(2., 2.),
+                glm::dvec2(0., 2.),
+            ]
+        );
+    }
+}
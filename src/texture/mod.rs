@@ -0,0 +1,353 @@
+mod nine_slice;
+mod repeat;
+
+pub use self::{
+    nine_slice::{Fill, Insets, NineSlice},
+    repeat::{Parallax, Repeat},
+};
+
+use crate::{
+    renderer::{
+        align,
+        options::{self, Options},
+        {Destination, Draw, FDestination, Position, Renderer, Show, Target},
+    },
+    resource, Result,
+};
+
+use std::rc::Rc;
+
+pub type Manager<'l, L> = resource::Manager<'l, String, <L as Loader<'l>>::Texture, L>;
+
+pub struct Image<T> {
+    pub texture: T,
+    pub dst: Destination,
+}
+
+impl<T> Image<T> {
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.dst = self.dst.scale(scale);
+        self
+    }
+
+    /// Scales by any factor without rounding, keeping the aligned point in
+    /// place
+    pub fn scale_by(self, scale: f64) -> PreciseImage<T> {
+        let x = match self.dst.pos.horizontal.align {
+            align::Horizontal::Left => 0.,
+            align::Horizontal::Center => 0.5,
+            align::Horizontal::Right => 1.,
+        };
+        let y = match self.dst.pos.vertical.align {
+            align::Vertical::Top => 0.,
+            align::Vertical::Middle => 0.5,
+            align::Vertical::Bottom => 1.,
+        };
+        PreciseImage {
+            texture: self.texture,
+            dst: self.dst.scale_by(scale),
+            anchor: glm::dvec2(x, y),
+        }
+    }
+}
+
+/// Image at a sub-pixel destination that is only rounded when drawn
+pub struct PreciseImage<T> {
+    pub texture: T,
+    pub dst: FDestination,
+    /// Point that stays in place when scaling, as a fraction of the dims
+    pub anchor: glm::DVec2,
+}
+
+impl<T> PreciseImage<T> {
+    pub fn scale_by(mut self, scale: f64) -> Self {
+        let anchor = self.dst.top_left + self.dst.dims * self.anchor;
+        let dims = self.dst.dims * scale;
+        self.dst = FDestination::new(anchor - dims * self.anchor, dims);
+        self
+    }
+}
+
+pub trait Texture: Sized {
+    fn dims(&self) -> glm::UVec2;
+
+    /// Default implementation for converting to an image
+    fn at(self, position: Position) -> Image<Self> {
+        let dst = position.dims(self.dims());
+        Image { texture: self, dst }
+    }
+}
+
+impl<T: Texture> Texture for Rc<T> {
+    fn dims(&self) -> glm::UVec2 {
+        self.as_ref().dims()
+    }
+}
+
+pub trait Loader<'a>: resource::Loader<'a, <Self as Loader<'a>>::Texture, Args = str> {
+    type Texture;
+}
+
+/// Loaders that can also create blank textures for a `Target` to draw into
+pub trait TargetLoader<'a>: Loader<'a> {
+    fn target(&'a self, dims: glm::UVec2) -> Result<Self::Texture>;
+}
+
+/// Draws `scene` once into a new texture so it can be reused like any other
+/// texture, e.g. to cache static UI or to upscale low-resolution art
+pub fn prerender<'a, L, C>(
+    loader: &'a L,
+    canvas: &mut C,
+    dims: glm::UVec2,
+    scene: &impl Show<C>,
+) -> Result<L::Texture>
+where
+    L: TargetLoader<'a>,
+    C: Target<L::Texture>,
+{
+    let mut texture = loader.target(dims)?;
+    canvas.render_to(&mut texture, scene)?;
+    Ok(texture)
+}
+
+impl<R: Renderer, T: Draw<R>> Show<R> for Image<T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.draw(&self.texture, options::at(self.dst))
+    }
+}
+
+impl<R: Renderer, T: Draw<R>> Draw<R> for Image<T> {
+    fn draw(&self, options: Options, renderer: &mut R) -> Result<()> {
+        renderer.draw(&self.texture, options.at(self.dst))
+    }
+}
+
+impl<R: Renderer, T: Draw<R>> Show<R> for PreciseImage<T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.draw(&self.texture, options::at_precise(self.dst))
+    }
+}
+
+impl<R: Renderer, T: Draw<R>> Draw<R> for PreciseImage<T> {
+    fn draw(&self, options: Options, renderer: &mut R) -> Result<()> {
+        renderer.draw(&self.texture, options.at_precise(self.dst))
+    }
+}
+
+#[cfg(test)]
+pub mod mocks {
+    use super::*;
+    use crate::renderer::{mocks::MockCanvas, Queue, Renderer, Transform, Transformed};
+
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub struct MockTexture {
+        pub dims: glm::UVec2,
+    }
+
+    impl Texture for MockTexture {
+        fn dims(&self) -> glm::UVec2 {
+            self.dims
+        }
+    }
+
+    impl Show<MockCanvas> for MockTexture {
+        fn show(&self, _: &mut MockCanvas) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    impl Draw<MockCanvas> for MockTexture {
+        fn draw(&self, options: Options, renderer: &mut MockCanvas) -> Result<()> {
+            let clipped = options.dst.map(|d| renderer.clipped_rect(d));
+            if clipped != Some(None) {
+                renderer.draw.push((*self, options));
+            }
+            Ok(())
+        }
+    }
+
+    pub struct MockLoader;
+
+    impl<'a> Loader<'a> for MockLoader {
+        type Texture = MockTexture;
+    }
+
+    impl resource::Loader<'_, MockTexture> for MockLoader {
+        type Args = str;
+
+        fn load(&self, _: &str) -> Result<MockTexture> {
+            unimplemented!()
+        }
+    }
+
+    impl TargetLoader<'_> for MockLoader {
+        fn target(&self, dims: glm::UVec2) -> Result<MockTexture> {
+            Ok(MockTexture { dims })
+        }
+    }
+
+    impl<X: Transform> Show<Transformed<'_, MockCanvas, X>> for MockTexture {
+        fn show(&self, renderer: &mut Transformed<'_, MockCanvas, X>) -> Result<()> {
+            renderer.show_inner(self)
+        }
+    }
+
+    impl<X: Transform> Draw<Transformed<'_, MockCanvas, X>> for MockTexture {
+        fn draw(
+            &self,
+            options: Options,
+            renderer: &mut Transformed<'_, MockCanvas, X>,
+        ) -> Result<()> {
+            renderer.draw_inner(self, options)
+        }
+    }
+
+    /// Copyable textures are queued by value
+    impl<'t, R: Renderer> Show<Queue<'t, R>> for MockTexture
+    where
+        Self: Show<R>,
+    {
+        fn show(&self, queue: &mut Queue<'t, R>) -> Result<()> {
+            let texture = *self;
+            queue.push(move |renderer| texture.show(renderer));
+            Ok(())
+        }
+    }
+
+    impl<'t, R: Renderer> Draw<Queue<'t, R>> for MockTexture
+    where
+        Self: Draw<R>,
+    {
+        fn draw(&self, options: Options, queue: &mut Queue<'t, R>) -> Result<()> {
+            let texture = *self;
+            queue.push(move |renderer| texture.draw(options, renderer));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{align, mocks::MockCanvas, options, ColorRGBA},
+        texture::mocks::{MockLoader, MockTexture},
+    };
+
+    #[test]
+    fn scale_image() {
+        let texture = MockTexture {
+            dims: glm::uvec2(25, 30),
+        };
+        let position = align::center(45).top(23);
+        let image = texture.at(position).scale(3);
+        assert_eq!(image.dst, position.dims(texture.dims * 3));
+    }
+
+    #[test]
+    fn scales_images_precisely() {
+        let texture = MockTexture {
+            dims: glm::uvec2(3, 3),
+        };
+        let image = texture.at(align::right(9).bottom(9)).scale_by(0.5);
+        let image = image.scale_by(3.);
+        let fdst = FDestination::new(glm::dvec2(4.5, 4.5), glm::dvec2(4.5, 4.5));
+        assert_eq!(image.dst, fdst);
+
+        let mut renderer = MockCanvas::new();
+        assert!(renderer.show(&image).is_ok());
+        assert_eq!(renderer.draw[0].1.precise_dst(), Some(fdst));
+        assert_eq!(renderer.draw[0].1.dst, Some(fdst.round()));
+    }
+
+    #[test]
+    fn draws_images() {
+        let mut renderer = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(25, 30),
+        };
+        let options = options::from(glm::uvec4(12, 34, 21, 34));
+        let position = align::left(45).top(23);
+        let image = texture.at(position);
+        assert!(renderer.draw(&image, options.clone()).is_ok());
+        assert_eq!(renderer.draw.len(), 1);
+        let dst = position.dims(texture.dims());
+        assert_eq!(renderer.draw[0], (texture, options.at(dst)));
+    }
+
+    #[test]
+    fn draws_with_color_and_blend() {
+        let mut renderer = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(25, 30),
+        };
+        let options = options::color(ColorRGBA(255, 0, 0, 255))
+            .alpha(128)
+            .blend(options::Blend::Add);
+        assert!(renderer.draw(&texture, options).is_ok());
+        assert_eq!(renderer.draw.len(), 1);
+        let (_, drawn) = &renderer.draw[0];
+        assert_eq!(drawn.color, Some(ColorRGBA(255, 0, 0, 128)));
+        assert_eq!(drawn.blend, Some(options::Blend::Add));
+    }
+
+    #[test]
+    fn shows_images() {
+        let mut renderer = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(25, 30),
+        };
+        let position = align::left(45).top(23);
+        let image = texture.at(position);
+        assert!(renderer.show(&image).is_ok());
+        assert_eq!(renderer.draw.len(), 1);
+        let dst = position.dims(texture.dims());
+        assert_eq!(renderer.draw[0], (texture, options::at(dst)));
+    }
+
+    #[test]
+    fn prerenders_scenes() {
+        let mut renderer = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(5, 5),
+        };
+        let scene = vec![
+            texture.at(align::left(0).top(0)),
+            texture.at(align::left(5).top(0)),
+        ];
+        let dims = glm::uvec2(10, 5);
+
+        let prerendered = prerender(&MockLoader, &mut renderer, dims, &scene).unwrap();
+        assert_eq!(prerendered.dims(), dims);
+        assert!(renderer.draw.is_empty());
+        assert_eq!(renderer.targets.len(), 1);
+        let (target, drawn) = &renderer.targets[0];
+        assert_eq!(*target, prerendered);
+        assert_eq!(drawn.draw.len(), 2);
+    }
+
+    #[test]
+    fn clips_draws() {
+        let mut renderer = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        let inside = texture.at(align::left(0).top(0));
+        let outside = texture.at(align::left(30).top(0));
+        let panel = align::left(0).top(0).dims(glm::uvec2(40, 20));
+        let list = align::left(0).top(0).dims(glm::uvec2(20, 20));
+
+        let result = renderer.clipped(panel, |r| {
+            r.clipped(list, |r| {
+                r.show(&inside)?;
+                r.show(&outside)
+            })?;
+            assert_eq!(r.clip, Some(panel));
+            r.show(&outside)
+        });
+        assert!(result.is_ok());
+        assert_eq!(renderer.clip, None);
+        assert_eq!(renderer.draw.len(), 2);
+        assert_eq!(renderer.draw[1].1.dst, Some(outside.dst));
+    }
+}
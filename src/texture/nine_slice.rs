@@ -0,0 +1,250 @@
+use super::{repeat, Texture};
+use crate::{
+    renderer::{align, Destination, Draw, Options, Renderer, Show},
+    Result,
+};
+
+/// Widths of the borders that are kept at their original size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Insets {
+    pub top: u32,
+    pub left: u32,
+    pub bottom: u32,
+    pub right: u32,
+}
+
+impl Insets {
+    pub fn uniform(inset: u32) -> Self {
+        Insets {
+            top: inset,
+            left: inset,
+            bottom: inset,
+            right: inset,
+        }
+    }
+}
+
+/// How the parts between the corners cover the space they are given
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Fill {
+    #[default]
+    Stretch,
+    Tile,
+}
+
+/// Texture split into a 3x3 grid whose corners keep their size while the
+/// edges and center grow to fill the destination
+pub struct NineSlice<T> {
+    pub texture: T,
+    pub insets: Insets,
+    pub dst: Destination,
+    pub edges: Fill,
+    pub center: Fill,
+}
+
+impl<T: Texture> NineSlice<T> {
+    pub fn new(texture: T, insets: Insets, dst: Destination) -> Self {
+        NineSlice {
+            texture,
+            insets,
+            dst,
+            edges: Fill::default(),
+            center: Fill::default(),
+        }
+    }
+
+    pub fn edges(mut self, edges: Fill) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    pub fn center(mut self, center: Fill) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// Source and destination rectangles of every piece that gets drawn
+    pub fn pieces(&self, dst: Destination) -> Vec<(glm::UVec4, Destination)> {
+        let dims = self.texture.dims();
+        let insets = self.insets;
+        let columns = slices(dims.x, dst.dims.x, insets.left, insets.right);
+        let rows = slices(dims.y, dst.dims.y, insets.top, insets.bottom);
+
+        rows.iter()
+            .enumerate()
+            .flat_map(|(r, &(src_y, src_h, dst_y, dst_h))| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(c, &(src_x, src_w, dst_x, dst_w))| {
+                        let src = glm::uvec4(src_x, src_y, src_w, src_h);
+                        let dst = align::left(dst.left() + dst_x as i32)
+                            .top(dst.top() + dst_y as i32)
+                            .dims(glm::uvec2(dst_w, dst_h));
+                        let fill = match (r, c) {
+                            (1, 1) => self.center,
+                            (1, _) | (_, 1) => self.edges,
+                            _ => Fill::Stretch,
+                        };
+                        match fill {
+                            Fill::Stretch => vec![(src, dst)],
+                            Fill::Tile => {
+                                let tile = glm::uvec2(src.z, src.w);
+                                repeat::cover(src, tile, dst, glm::ivec2(0, 0))
+                            }
+                        }
+                    })
+            })
+            .filter(|(src, dst)| src.z > 0 && src.w > 0 && dst.dims.x > 0 && dst.dims.y > 0)
+            .collect()
+    }
+}
+
+/// Splits a length into the start, middle and end slices of both the
+/// source and the destination, with borders that do not fit clamped to half
+/// of the length on either side
+fn slices(src: u32, dst: u32, start: u32, end: u32) -> [(u32, u32, u32, u32); 3] {
+    let clamp = |len: u32| (start.min(len / 2), end.min(len - len / 2));
+    let (src_start, src_end) = clamp(src);
+    let (dst_start, dst_end) = clamp(dst);
+    [
+        (0, src_start, 0, dst_start),
+        (
+            src_start,
+            src - src_start - src_end,
+            dst_start,
+            dst - dst_start - dst_end,
+        ),
+        (src - src_end, src_end, dst - dst_end, dst_end),
+    ]
+}
+
+impl<R: Renderer, T: Texture + Draw<R>> Show<R> for NineSlice<T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.draw(self, Options::default())
+    }
+}
+
+/// Draws into the destination of the options if there is one; rotations and
+/// flips are dropped since each piece would turn on its own
+impl<R: Renderer, T: Texture + Draw<R>> Draw<R> for NineSlice<T> {
+    fn draw(&self, mut options: Options, renderer: &mut R) -> Result<()> {
+        options.rotation = None;
+        options.flip = None;
+        let dst = options.dst.unwrap_or(self.dst);
+        self.pieces(dst).into_iter().try_for_each(|(src, dst)| {
+            renderer.draw(&self.texture, options.clone().from(src).at(dst))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        renderer::{mocks::MockCanvas, options},
+        texture::mocks::MockTexture,
+    };
+
+    fn panel(dst: Destination) -> NineSlice<MockTexture> {
+        let texture = MockTexture {
+            dims: glm::uvec2(12, 12),
+        };
+        NineSlice::new(texture, Insets::uniform(4), dst)
+    }
+
+    #[test]
+    fn stretches_edges_and_center() {
+        let dst = align::left(10).top(20).dims(glm::uvec2(30, 20));
+        let pieces = panel(dst).pieces(dst);
+        assert_eq!(pieces.len(), 9);
+
+        let corners = [pieces[0], pieces[2], pieces[6], pieces[8]];
+        assert!(corners
+            .iter()
+            .all(|(src, dst)| src.z == 4 && src.w == 4 && dst.dims == glm::uvec2(4, 4)));
+        assert_eq!(
+            pieces[4],
+            (
+                glm::uvec4(4, 4, 4, 4),
+                align::left(14).top(24).dims(glm::uvec2(22, 12))
+            )
+        );
+        assert_eq!(pieces[8].1, align::left(36).top(36).dims(glm::uvec2(4, 4)));
+    }
+
+    #[test]
+    fn tiles_edges() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(18, 12));
+        let pieces = panel(dst).edges(Fill::Tile).pieces(dst);
+        let top: Vec<_> = pieces.iter().filter(|(_, d)| d.top() == 0).collect();
+        assert_eq!(
+            top.iter().map(|(s, _)| s.z).collect::<Vec<_>>(),
+            vec![4, 4, 4, 2, 4]
+        );
+        assert_eq!(
+            top.iter().map(|(_, d)| d.left()).collect::<Vec<_>>(),
+            vec![0, 4, 8, 12, 14]
+        );
+    }
+
+    #[test]
+    fn shrinks_borders_that_do_not_fit() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(6, 12));
+        let pieces = panel(dst).pieces(dst);
+        assert_eq!(pieces.len(), 6);
+        assert!(pieces.iter().all(|(_, d)| d.dims.x == 3));
+    }
+
+    #[test]
+    fn clamps_borders_to_half() {
+        let texture = MockTexture {
+            dims: glm::uvec2(12, 12),
+        };
+        let insets = Insets {
+            left: 10,
+            right: 2,
+            ..Insets::uniform(4)
+        };
+        let dst = align::left(0).top(0).dims(glm::uvec2(9, 12));
+        let pieces = NineSlice::new(texture, insets, dst).pieces(dst);
+        let top: Vec<_> = pieces.iter().filter(|(_, d)| d.top() == 0).collect();
+        assert_eq!(
+            top.iter()
+                .map(|(s, d)| (s.x, s.z, d.dims.x))
+                .collect::<Vec<_>>(),
+            vec![(0, 6, 4), (6, 4, 3), (10, 2, 2)]
+        );
+    }
+
+    #[test]
+    fn ignores_rotations_and_flips() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(20, 20));
+        let rotation = options::Rotation {
+            angle: 90.,
+            center: glm::ivec2(10, 10),
+        };
+        let options = options::rotate(rotation).flip(options::Flip::Both);
+        let mut renderer = MockCanvas::new();
+        assert!(renderer.draw(&panel(dst), options).is_ok());
+        assert_eq!(renderer.draw.len(), 9);
+        assert!(renderer
+            .draw
+            .iter()
+            .all(|(_, o)| o.rotation.is_none() && o.flip.is_none()));
+    }
+
+    #[test]
+    fn draws_pieces() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(20, 20));
+        let panel = panel(dst);
+        let mut renderer = MockCanvas::new();
+        assert!(renderer.show(&panel).is_ok());
+        assert_eq!(renderer.draw.len(), 9);
+        assert!(renderer
+            .draw
+            .iter()
+            .zip(panel.pieces(dst))
+            .all(|((_, o), (src, dst))| o.src == Some(src) && o.dst == Some(dst)));
+    }
+}
@@ -0,0 +1,222 @@
+use super::Texture;
+use crate::{
+    renderer::{align, Destination, Draw, Options, Renderer, Show},
+    Result,
+};
+
+/// Texture repeated over a destination, e.g. for floors and backgrounds
+pub struct Repeat<T> {
+    pub texture: T,
+    pub dst: Destination,
+    /// How far the pattern has been scrolled, wrapping around every tile
+    pub offset: glm::IVec2,
+    pub scale: f64,
+}
+
+impl<T: Texture> Repeat<T> {
+    pub fn new(texture: T, dst: Destination) -> Self {
+        Repeat {
+            texture,
+            dst,
+            offset: glm::ivec2(0, 0),
+            scale: 1.,
+        }
+    }
+
+    pub fn offset(mut self, offset: glm::IVec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Source and destination rectangles of every tile that gets drawn
+    pub fn pieces(&self, dst: Destination) -> Vec<(glm::UVec4, Destination)> {
+        let dims = self.texture.dims();
+        let tile = glm::to_uvec2(glm::round(glm::to_dvec2(dims) * self.scale));
+        cover(glm::uvec4(0, 0, dims.x, dims.y), tile, dst, self.offset)
+    }
+}
+
+/// Layer that scrolls slower or faster than the camera to fake depth
+pub struct Parallax<T> {
+    pub layer: Repeat<T>,
+    /// Fraction of the scroll that is applied to the layer; distant layers
+    /// use values below one
+    pub factor: glm::DVec2,
+}
+
+impl<T> Parallax<T> {
+    pub fn new(layer: Repeat<T>, factor: glm::DVec2) -> Self {
+        Parallax { layer, factor }
+    }
+
+    pub fn scroll_to(&mut self, scroll: glm::DVec2) {
+        self.layer.offset = glm::to_ivec2(glm::round(scroll * self.factor));
+    }
+}
+
+/// Covers `dst` with copies of `src`, each drawn at `tile` size, starting
+/// `offset` into the pattern; tiles that are cut off by the edges of `dst`
+/// only use the matching part of `src`
+pub(super) fn cover(
+    src: glm::UVec4,
+    tile: glm::UVec2,
+    dst: Destination,
+    offset: glm::IVec2,
+) -> Vec<(glm::UVec4, Destination)> {
+    let columns = spans(src.x, src.z, tile.x, dst.dims.x, offset.x);
+    let rows = spans(src.y, src.w, tile.y, dst.dims.y, offset.y);
+    rows.iter()
+        .flat_map(|&(src_y, src_h, y, h)| {
+            columns.iter().map(move |&(src_x, src_w, x, w)| {
+                let at = align::left(dst.left() + x as i32)
+                    .top(dst.top() + y as i32)
+                    .dims(glm::uvec2(w, h));
+                (glm::uvec4(src_x, src_y, src_w, src_h), at)
+            })
+        })
+        .collect()
+}
+
+/// Splits `length` into `(src start, src length, start, length)` spans of
+/// `size` that map onto `src_len` source pixels from `src_start`
+fn spans(
+    src_start: u32,
+    src_len: u32,
+    size: u32,
+    length: u32,
+    offset: i32,
+) -> Vec<(u32, u32, u32, u32)> {
+    if size == 0 || src_len == 0 {
+        return vec![];
+    }
+    let (size, length) = (i64::from(size), i64::from(length));
+    let to_src = |pixels: i64| (pixels * i64::from(src_len) + size / 2) / size;
+    let first = -i64::from(offset).rem_euclid(size);
+
+    (0..)
+        .map(|i| first + i * size)
+        .take_while(|&start| start < length)
+        .filter_map(|start| {
+            let (visible_start, visible_end) = (start.max(0), (start + size).min(length));
+            let src_from = to_src(visible_start - start);
+            let src_to = to_src(visible_end - start);
+            if src_to > src_from {
+                Some((
+                    src_start + src_from as u32,
+                    (src_to - src_from) as u32,
+                    visible_start as u32,
+                    (visible_end - visible_start) as u32,
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+impl<R: Renderer, T: Texture + Draw<R>> Show<R> for Repeat<T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.draw(self, Options::default())
+    }
+}
+
+/// Draws into the destination of the options if there is one
+impl<R: Renderer, T: Texture + Draw<R>> Draw<R> for Repeat<T> {
+    fn draw(&self, options: Options, renderer: &mut R) -> Result<()> {
+        let dst = options.dst.unwrap_or(self.dst);
+        self.pieces(dst).into_iter().try_for_each(|(src, dst)| {
+            renderer.draw(&self.texture, options.clone().from(src).at(dst))
+        })
+    }
+}
+
+impl<R: Renderer, T: Texture + Draw<R>> Show<R> for Parallax<T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        renderer.show(&self.layer)
+    }
+}
+
+impl<R: Renderer, T: Texture + Draw<R>> Draw<R> for Parallax<T> {
+    fn draw(&self, options: Options, renderer: &mut R) -> Result<()> {
+        renderer.draw(&self.layer, options)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{renderer::mocks::MockCanvas, texture::mocks::MockTexture};
+
+    fn floor(dst: Destination) -> Repeat<MockTexture> {
+        let texture = MockTexture {
+            dims: glm::uvec2(16, 8),
+        };
+        Repeat::new(texture, dst)
+    }
+
+    #[test]
+    fn crops_edge_tiles() {
+        let dst = align::left(10).top(10).dims(glm::uvec2(40, 10));
+        let pieces = floor(dst).pieces(dst);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(
+            pieces[2],
+            (
+                glm::uvec4(0, 0, 8, 8),
+                align::left(42).top(10).dims(glm::uvec2(8, 8))
+            )
+        );
+        assert_eq!(
+            pieces[5],
+            (
+                glm::uvec4(0, 0, 8, 2),
+                align::left(42).top(18).dims(glm::uvec2(8, 2))
+            )
+        );
+    }
+
+    #[test]
+    fn offsets_pattern() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(32, 8));
+        let pieces = floor(dst).offset(glm::ivec2(4, 0)).pieces(dst);
+        let columns: Vec<_> = pieces.iter().map(|(s, d)| (s.x, s.z, d.left())).collect();
+        assert_eq!(columns, vec![(4, 12, 0), (0, 16, 12), (0, 4, 28)]);
+
+        let pieces = floor(dst).offset(glm::ivec2(-4, 0)).pieces(dst);
+        let columns: Vec<_> = pieces.iter().map(|(s, d)| (s.x, s.z, d.left())).collect();
+        assert_eq!(columns, vec![(12, 4, 0), (0, 16, 4), (0, 12, 20)]);
+    }
+
+    #[test]
+    fn scales_tiles() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(40, 16));
+        let pieces = floor(dst).scale(2.).pieces(dst);
+        let columns: Vec<_> = pieces
+            .iter()
+            .map(|(s, d)| (s.x, s.z, d.left(), d.dims.x))
+            .collect();
+        assert_eq!(columns, vec![(0, 16, 0, 32), (0, 4, 32, 8)]);
+    }
+
+    #[test]
+    fn scrolls_parallax() {
+        let dst = align::left(0).top(0).dims(glm::uvec2(32, 8));
+        let mut parallax = Parallax::new(floor(dst), glm::dvec2(0.5, 0.));
+        parallax.scroll_to(glm::dvec2(20., 100.));
+        assert_eq!(parallax.layer.offset, glm::ivec2(10, 0));
+
+        let mut renderer = MockCanvas::new();
+        assert!(renderer.show(&parallax).is_ok());
+        let (_, first) = &renderer.draw[0];
+        assert_eq!(first.src, Some(glm::uvec4(10, 0, 6, 8)));
+        assert_eq!(
+            first.dst,
+            Some(align::left(0).top(0).dims(glm::uvec2(6, 8)))
+        );
+    }
+}
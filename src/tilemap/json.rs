@@ -0,0 +1,567 @@
+use super::{
+    decode_base64, parse_color, resolve, tmx, Data, GroupLayer, Layer, Object, ObjectLayer,
+    ObjectShape, Properties, Property, TileLayer, TilesetData,
+};
+use crate::{animation::Grid, Result};
+
+use serde::Deserialize;
+
+use std::{fs, path::Path};
+
+pub(super) fn parse(json: &str, dir: &Path) -> Result<Data> {
+    let raw: RawMap = serde_json::from_str(json)?;
+    if raw.orientation != "orthogonal" {
+        return Err(failure::err_msg(format!(
+            "{} maps are not supported",
+            raw.orientation
+        )));
+    }
+    if raw.infinite {
+        return Err(failure::err_msg("infinite maps are not supported"));
+    }
+    if raw.tilewidth == 0 || raw.tileheight == 0 {
+        return Err(failure::err_msg(format!(
+            "map tiles must not be empty but are {}x{}",
+            raw.tilewidth, raw.tileheight
+        )));
+    }
+
+    let mut layers = vec![];
+    flatten(raw.layers, glm::ivec2(0, 0), true, &mut layers)?;
+
+    let tilesets = raw
+        .tilesets
+        .into_iter()
+        .map(|t| t.into_data(None, dir))
+        .collect::<Result<_>>()?;
+
+    Ok(Data {
+        dims: glm::uvec2(raw.width, raw.height),
+        tile: glm::uvec2(raw.tilewidth, raw.tileheight),
+        layers,
+        tilesets,
+        properties: properties(raw.properties)?,
+    })
+}
+
+pub(super) fn parse_tileset(json: &str, first_gid: u32, dir: &Path) -> Result<TilesetData> {
+    let raw: RawTileset = serde_json::from_str(json)?;
+    raw.into_data(Some(first_gid), dir)
+}
+
+/// Group layers are followed by their children, which inherit the group's
+/// offset and visibility
+fn flatten(
+    raw: Vec<RawLayer>,
+    offset: glm::IVec2,
+    visible: bool,
+    layers: &mut Vec<Layer>,
+) -> Result<()> {
+    for layer in raw {
+        match layer {
+            RawLayer::Tiles {
+                name,
+                visible: layer_visible,
+                width,
+                height,
+                data,
+                encoding,
+                compression,
+                offsetx,
+                offsety,
+                properties: props,
+            } => {
+                if width == 0 || height == 0 {
+                    return Err(failure::err_msg(format!(
+                        "layer '{}' must not be empty",
+                        name
+                    )));
+                }
+                let tiles = match (data, encoding.as_deref()) {
+                    (RawData::Array(tiles), _) => tiles,
+                    (RawData::Encoded(data), Some("base64")) => match compression.as_ref() {
+                        Some(c) if !c.is_empty() => {
+                            return Err(failure::err_msg(format!(
+                                "{} compressed layer data is not supported",
+                                c
+                            )))
+                        }
+                        _ => decode_base64(&data)?,
+                    },
+                    (RawData::Encoded(_), _) => {
+                        return Err(failure::err_msg(format!(
+                            "layer '{}' has an unsupported encoding",
+                            name
+                        )))
+                    }
+                };
+                let count = u64::from(width) * u64::from(height);
+                if tiles.len() as u64 != count {
+                    return Err(failure::err_msg(format!(
+                        "layer '{}' has {} tiles but should have {}",
+                        name,
+                        tiles.len(),
+                        count
+                    )));
+                }
+                layers.push(Layer::Tiles(TileLayer {
+                    name,
+                    visible: visible && layer_visible,
+                    offset: offset + glm::ivec2(offsetx as i32, offsety as i32),
+                    dims: glm::uvec2(width, height),
+                    tiles,
+                    properties: properties(props)?,
+                }));
+            }
+            RawLayer::Objects {
+                name,
+                visible: layer_visible,
+                offsetx,
+                offsety,
+                objects,
+                properties: props,
+            } => {
+                let objects = objects
+                    .into_iter()
+                    .map(RawObject::into_object)
+                    .collect::<Result<_>>()?;
+                layers.push(Layer::Objects(ObjectLayer {
+                    name,
+                    visible: visible && layer_visible,
+                    offset: offset + glm::ivec2(offsetx as i32, offsety as i32),
+                    objects,
+                    properties: properties(props)?,
+                }));
+            }
+            RawLayer::Group {
+                name,
+                visible: group_visible,
+                offsetx,
+                offsety,
+                layers: children,
+                properties: props,
+            } => {
+                let offset = offset + glm::ivec2(offsetx as i32, offsety as i32);
+                let visible = visible && group_visible;
+                let index = layers.len();
+                layers.push(Layer::Group(GroupLayer {
+                    name,
+                    visible,
+                    offset,
+                    layers: 0,
+                    properties: properties(props)?,
+                }));
+                flatten(children, offset, visible, layers)?;
+                let count = layers.len() - index - 1;
+                if let Layer::Group(group) = &mut layers[index] {
+                    group.layers = count;
+                }
+            }
+            RawLayer::Image {} => {}
+        }
+    }
+    Ok(())
+}
+
+fn properties(raw: Vec<RawProperty>) -> Result<Properties> {
+    raw.into_iter()
+        .map(|p| {
+            let value = match (p.kind.as_str(), p.value) {
+                ("bool", serde_json::Value::Bool(b)) => Property::Bool(b),
+                ("int", serde_json::Value::Number(n)) if n.is_i64() => {
+                    Property::Int(n.as_i64().unwrap_or_default())
+                }
+                ("float", serde_json::Value::Number(n)) => {
+                    Property::Float(n.as_f64().unwrap_or_default())
+                }
+                ("object", serde_json::Value::Number(n)) => {
+                    Property::Object(n.as_u64().unwrap_or_default() as u32)
+                }
+                ("color", serde_json::Value::String(s)) => Property::Color(parse_color(&s)?),
+                ("file", serde_json::Value::String(s)) => Property::File(s),
+                ("string", serde_json::Value::String(s)) => Property::String(s),
+                (kind, value) => {
+                    return Err(failure::err_msg(format!(
+                        "property '{}' has unsupported {} value {}",
+                        p.name, kind, value
+                    )))
+                }
+            };
+            Ok((p.name, value))
+        })
+        .collect()
+}
+
+fn orthogonal() -> String {
+    "orthogonal".into()
+}
+
+fn visible() -> bool {
+    true
+}
+
+fn string() -> String {
+    "string".into()
+}
+
+#[derive(Deserialize)]
+struct RawMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default = "orthogonal")]
+    orientation: String,
+    #[serde(default)]
+    infinite: bool,
+    layers: Vec<RawLayer>,
+    #[serde(default)]
+    tilesets: Vec<RawTileset>,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum RawLayer {
+    #[serde(rename = "tilelayer")]
+    Tiles {
+        name: String,
+        #[serde(default = "visible")]
+        visible: bool,
+        width: u32,
+        height: u32,
+        data: RawData,
+        encoding: Option<String>,
+        compression: Option<String>,
+        #[serde(default)]
+        offsetx: f64,
+        #[serde(default)]
+        offsety: f64,
+        #[serde(default)]
+        properties: Vec<RawProperty>,
+    },
+    #[serde(rename = "objectgroup")]
+    Objects {
+        name: String,
+        #[serde(default = "visible")]
+        visible: bool,
+        #[serde(default)]
+        offsetx: f64,
+        #[serde(default)]
+        offsety: f64,
+        objects: Vec<RawObject>,
+        #[serde(default)]
+        properties: Vec<RawProperty>,
+    },
+    #[serde(rename = "group")]
+    Group {
+        #[serde(default)]
+        name: String,
+        #[serde(default = "visible")]
+        visible: bool,
+        #[serde(default)]
+        offsetx: f64,
+        #[serde(default)]
+        offsety: f64,
+        layers: Vec<RawLayer>,
+        #[serde(default)]
+        properties: Vec<RawProperty>,
+    },
+    #[serde(rename = "imagelayer")]
+    Image {},
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawData {
+    Array(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Deserialize)]
+struct RawObject {
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    class: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    width: f64,
+    #[serde(default)]
+    height: f64,
+    gid: Option<u32>,
+    #[serde(default)]
+    ellipse: bool,
+    #[serde(default)]
+    point: bool,
+    polygon: Option<Vec<RawPoint>>,
+    polyline: Option<Vec<RawPoint>>,
+    #[serde(default = "visible")]
+    visible: bool,
+    #[serde(default)]
+    properties: Vec<RawProperty>,
+}
+
+#[derive(Deserialize)]
+struct RawPoint {
+    x: f64,
+    y: f64,
+}
+
+#[derive(Deserialize)]
+struct RawTileset {
+    firstgid: Option<u32>,
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    image: Option<String>,
+    tilewidth: Option<u32>,
+    tileheight: Option<u32>,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+    tilecount: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RawProperty {
+    name: String,
+    #[serde(default = "string", rename = "type")]
+    kind: String,
+    value: serde_json::Value,
+}
+
+impl RawObject {
+    fn into_object(self) -> Result<Object> {
+        let points =
+            |points: Vec<RawPoint>| points.into_iter().map(|p| glm::dvec2(p.x, p.y)).collect();
+        let shape = match (self.polygon, self.polyline) {
+            (Some(polygon), _) => ObjectShape::Polygon(points(polygon)),
+            (_, Some(polyline)) => ObjectShape::Polyline(points(polyline)),
+            _ if self.ellipse => ObjectShape::Ellipse,
+            _ if self.point => ObjectShape::Point,
+            _ => ObjectShape::Rectangle,
+        };
+        Ok(Object {
+            id: self.id,
+            name: self.name,
+            kind: if self.kind.is_empty() {
+                self.class
+            } else {
+                self.kind
+            },
+            position: glm::dvec2(self.x, self.y),
+            dims: glm::dvec2(self.width, self.height),
+            gid: self.gid,
+            shape,
+            visible: self.visible,
+            properties: properties(self.properties)?,
+        })
+    }
+}
+
+impl RawTileset {
+    fn into_data(self, first_gid: Option<u32>, dir: &Path) -> Result<TilesetData> {
+        let first_gid = self
+            .firstgid
+            .or(first_gid)
+            .ok_or_else(|| failure::err_msg("tileset is missing its firstgid"))?;
+
+        if let Some(source) = self.source {
+            let path = dir.join(&source);
+            let contents = fs::read_to_string(&path)?;
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            return if source.ends_with(".tsx") {
+                tmx::parse_tileset(&contents, first_gid, dir)
+            } else {
+                parse_tileset(&contents, first_gid, dir)
+            };
+        }
+
+        match (self.image, self.tilewidth, self.tileheight, self.tilecount) {
+            (Some(image), Some(width), Some(height), Some(count)) => Ok(TilesetData {
+                first_gid,
+                name: self.name,
+                image: resolve(dir, &image),
+                grid: Grid::new(glm::uvec2(width, height))
+                    .margin(self.margin)
+                    .spacing(self.spacing),
+                count,
+            }),
+            _ => Err(failure::err_msg(format!(
+                "tileset '{}' must be a single image with uniform tiles",
+                self.name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = r##"{
+        "width": 3, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "orientation": "orthogonal", "infinite": false,
+        "properties": [
+            { "name": "music", "type": "file", "value": "level1.ogg" },
+            { "name": "gravity", "type": "float", "value": 9.8 }
+        ],
+        "layers": [
+            {
+                "type": "tilelayer", "name": "ground", "width": 3, "height": 2,
+                "data": [1, 2, 0, 2147483651, 0, 4], "visible": true,
+                "properties": [{ "name": "solid", "type": "bool", "value": true }]
+            },
+            {
+                "type": "group", "name": "decor", "offsetx": 4, "offsety": 2, "visible": false,
+                "properties": [{ "name": "depth", "type": "int", "value": 2 }],
+                "layers": [
+                    {
+                        "type": "tilelayer", "name": "flowers", "width": 3, "height": 2,
+                        "encoding": "base64", "data": "AQAAAAAAAAAAAAAAAAAAAAAAAAACAAAA",
+                        "offsetx": 1
+                    }
+                ]
+            },
+            { "type": "imagelayer", "name": "sky", "image": "sky.png" },
+            {
+                "type": "objectgroup", "name": "spawns",
+                "objects": [
+                    { "id": 1, "name": "player", "class": "spawn", "x": 8, "y": 24,
+                      "width": 16, "height": 16, "visible": true },
+                    { "id": 2, "type": "path", "x": 0, "y": 0, "visible": true,
+                      "polyline": [{ "x": 0, "y": 0 }, { "x": 10, "y": 5 }],
+                      "properties": [{ "name": "color", "type": "color", "value": "#ff00ff00" }] }
+                ]
+            }
+        ],
+        "tilesets": [
+            {
+                "firstgid": 1, "name": "terrain", "image": "images/terrain.png",
+                "tilewidth": 16, "tileheight": 16, "margin": 1, "spacing": 2, "tilecount": 12,
+                "columns": 4, "imagewidth": 70, "imageheight": 52
+            }
+        ]
+    }"##;
+
+    #[test]
+    fn parses_map() {
+        let data = parse(MAP, Path::new("maps")).unwrap();
+        assert_eq!(data.dims, glm::uvec2(3, 2));
+        assert_eq!(data.tile, glm::uvec2(16, 16));
+        assert_eq!(
+            data.properties["music"],
+            Property::File("level1.ogg".into())
+        );
+        assert_eq!(data.properties["gravity"], Property::Float(9.8));
+        assert_eq!(
+            data.tilesets,
+            vec![TilesetData {
+                first_gid: 1,
+                name: "terrain".into(),
+                image: Path::new("maps/images/terrain.png")
+                    .to_string_lossy()
+                    .into_owned(),
+                grid: Grid::new(glm::uvec2(16, 16)).margin(1).spacing(2),
+                count: 12,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_layers() {
+        let data = parse(MAP, Path::new("")).unwrap();
+        assert_eq!(data.layers.len(), 4);
+
+        match &data.layers[0] {
+            Layer::Tiles(layer) => {
+                assert_eq!(layer.tiles, vec![1, 2, 0, 0x8000_0003, 0, 4]);
+                assert_eq!(layer.properties["solid"], Property::Bool(true));
+            }
+            l => panic!("expected a tile layer; got {:?}", l),
+        }
+
+        match &data.layers[1] {
+            Layer::Group(group) => {
+                assert_eq!(group.name, "decor");
+                assert!(!group.visible);
+                assert_eq!(group.offset, glm::ivec2(4, 2));
+                assert_eq!(group.layers, 1);
+                assert_eq!(group.properties["depth"], Property::Int(2));
+            }
+            l => panic!("expected a group layer; got {:?}", l),
+        }
+
+        match &data.layers[2] {
+            Layer::Tiles(layer) => {
+                assert_eq!(layer.name, "flowers");
+                assert!(!layer.visible);
+                assert_eq!(layer.offset, glm::ivec2(5, 2));
+                assert_eq!(layer.tiles, vec![1, 0, 0, 0, 0, 2]);
+            }
+            l => panic!("expected a tile layer; got {:?}", l),
+        }
+
+        match &data.layers[3] {
+            Layer::Objects(layer) => {
+                assert_eq!(layer.objects[0].kind, "spawn");
+                assert_eq!(layer.objects[0].shape, ObjectShape::Rectangle);
+                assert_eq!(layer.objects[1].kind, "path");
+                assert_eq!(
+                    layer.objects[1].shape,
+                    ObjectShape::Polyline(vec![glm::dvec2(0., 0.), glm::dvec2(10., 5.)])
+                );
+                assert_eq!(
+                    layer.objects[1].properties["color"],
+                    Property::Color(crate::renderer::ColorRGBA(0, 255, 0, 255))
+                );
+            }
+            l => panic!("expected an object layer; got {:?}", l),
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_maps() {
+        let isometric = MAP.replace(r#""orthogonal""#, r#""isometric""#);
+        assert!(parse(&isometric, Path::new("")).is_err());
+
+        let infinite = MAP.replace(r#""infinite": false"#, r#""infinite": true"#);
+        assert!(parse(&infinite, Path::new("")).is_err());
+
+        let compressed = MAP.replace(
+            r#""encoding": "base64","#,
+            r#""encoding": "base64", "compression": "zlib","#,
+        );
+        assert!(parse(&compressed, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_tiles_and_layers() {
+        let no_tiles = MAP.replacen(r#""tilewidth": 16"#, r#""tilewidth": 0"#, 1);
+        assert!(parse(&no_tiles, Path::new("")).is_err());
+
+        let no_columns = MAP.replace(
+            r#""name": "ground", "width": 3, "height": 2,
+                "data": [1, 2, 0, 2147483651, 0, 4]"#,
+            r#""name": "ground", "width": 0, "height": 2,
+                "data": []"#,
+        );
+        assert!(parse(&no_columns, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn rejects_overflowing_layers() {
+        let huge = MAP.replace(
+            r#""name": "ground", "width": 3, "height": 2,"#,
+            r#""name": "ground", "width": 65536, "height": 65536,"#,
+        );
+        assert!(parse(&huge, Path::new("")).is_err());
+    }
+}
@@ -0,0 +1,693 @@
+mod json;
+mod tmx;
+
+use crate::{
+    animation::{Grid, Tile, TileSheet},
+    renderer::{
+        align,
+        options::{self, Flip, Rotation},
+        ColorRGBA, Draw, Options, Renderer, Show,
+    },
+    shape::Rectangle,
+    texture::{self, Texture},
+    Result,
+};
+
+use base64::Engine;
+
+use std::{collections::HashMap, fs, path::Path, rc::Rc};
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+const ROTATED_HEXAGONAL: u32 = 0x1000_0000;
+const GID_MASK: u32 =
+    !(FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY | FLIPPED_DIAGONALLY | ROTATED_HEXAGONAL);
+
+pub type Properties = HashMap<String, Property>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Property {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Color(ColorRGBA),
+    File(String),
+    Object(u32),
+}
+
+/// A Tiled map whose tilesets have not been loaded yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Data {
+    pub dims: glm::UVec2,
+    pub tile: glm::UVec2,
+    pub layers: Vec<Layer>,
+    pub tilesets: Vec<TilesetData>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TilesetData {
+    pub first_gid: u32,
+    pub name: String,
+    pub image: String,
+    pub grid: Grid,
+    pub count: u32,
+}
+
+#[derive(Debug)]
+pub struct Map<T> {
+    pub dims: glm::UVec2,
+    pub tile: glm::UVec2,
+    pub layers: Vec<Layer>,
+    pub tilesets: Vec<Tileset<T>>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tileset<T> {
+    pub first_gid: u32,
+    pub count: u32,
+    pub tile: glm::UVec2,
+    pub sheet: TileSheet<T>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+    Group(GroupLayer),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+    pub visible: bool,
+    pub offset: glm::IVec2,
+    pub dims: glm::UVec2,
+    /// Global tile ids, including Tiled's flip flags, in row-major order
+    pub tiles: Vec<u32>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub visible: bool,
+    pub offset: glm::IVec2,
+    pub objects: Vec<Object>,
+    pub properties: Properties,
+}
+
+/// Group layers are flattened into their children, which follow it and
+/// inherit its offset and visibility
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupLayer {
+    pub name: String,
+    pub visible: bool,
+    pub offset: glm::IVec2,
+    /// How many of the following layers are in the group, including those in
+    /// nested groups
+    pub layers: usize,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    pub id: u32,
+    pub name: String,
+    pub kind: String,
+    pub position: glm::DVec2,
+    pub dims: glm::DVec2,
+    pub gid: Option<u32>,
+    pub shape: ObjectShape,
+    pub visible: bool,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    Polygon(Vec<glm::DVec2>),
+    Polyline(Vec<glm::DVec2>),
+}
+
+pub struct View<'a, T> {
+    map: &'a Map<T>,
+    camera: glm::IVec2,
+    viewport: glm::UVec2,
+}
+
+impl Data {
+    pub fn parse_tmx(xml: &str) -> Result<Data> {
+        tmx::parse(xml, Path::new(""))
+    }
+
+    pub fn parse_json(json: &str) -> Result<Data> {
+        json::parse(json, Path::new(""))
+    }
+
+    /// Reads a `.tmx` or `.json`/`.tmj` map, resolving tilesets and their
+    /// images relative to it
+    pub fn load(path: impl AsRef<Path>) -> Result<Data> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("tmx") => tmx::parse(&contents, dir),
+            Some("json") | Some("tmj") => json::parse(&contents, dir),
+            _ => Err(failure::err_msg(format!(
+                "unrecognized map format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn into_map<'l, L>(
+        self,
+        textures: &mut texture::Manager<'l, L>,
+    ) -> Result<Map<Rc<L::Texture>>>
+    where
+        L: texture::Loader<'l>,
+        L::Texture: Texture,
+    {
+        self.into_map_with(|image| textures.load(image))
+    }
+
+    pub fn into_map_with<T: Texture>(
+        self,
+        mut load: impl FnMut(&str) -> Result<T>,
+    ) -> Result<Map<T>> {
+        let tilesets = self
+            .tilesets
+            .into_iter()
+            .map(|t| {
+                let texture = load(&t.image)?;
+                Ok(Tileset {
+                    first_gid: t.first_gid,
+                    count: t.count,
+                    tile: t.grid.tile,
+                    sheet: TileSheet::from_grid(t.grid, texture)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Map {
+            dims: self.dims,
+            tile: self.tile,
+            layers: self.layers,
+            tilesets,
+            properties: self.properties,
+        })
+    }
+}
+
+impl<T> Map<T> {
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name() == name)
+    }
+
+    /// Looks up a tile by its global id, ignoring any flip flags
+    pub fn tile(&self, gid: u32) -> Option<Tile<'_, T>> {
+        let gid = gid & GID_MASK;
+        self.tileset(gid).map(|t| t.sheet.tile(gid - t.first_gid))
+    }
+
+    /// Collision rectangles for the named layer in map coordinates
+    pub fn rectangles(&self, layer: &str) -> Vec<Rectangle> {
+        match self.layer(layer) {
+            Some(Layer::Tiles(layer)) => layer.rectangles(self.tile),
+            Some(Layer::Objects(layer)) => layer.rectangles(),
+            Some(Layer::Group(_)) | None => vec![],
+        }
+    }
+
+    /// The part of the map seen through a viewport whose top left corner is
+    /// at `camera` in map coordinates
+    pub fn view(&self, camera: glm::IVec2, viewport: glm::UVec2) -> View<'_, T> {
+        View {
+            map: self,
+            camera,
+            viewport,
+        }
+    }
+
+    fn tileset(&self, gid: u32) -> Option<&Tileset<T>> {
+        self.tilesets
+            .iter()
+            .rev()
+            .find(|t| t.first_gid <= gid)
+            .filter(|t| gid - t.first_gid < t.count)
+    }
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        match self {
+            Layer::Tiles(l) => &l.name,
+            Layer::Objects(l) => &l.name,
+            Layer::Group(l) => &l.name,
+        }
+    }
+
+    pub fn visible(&self) -> bool {
+        match self {
+            Layer::Tiles(l) => l.visible,
+            Layer::Objects(l) => l.visible,
+            Layer::Group(l) => l.visible,
+        }
+    }
+
+    pub fn properties(&self) -> &Properties {
+        match self {
+            Layer::Tiles(l) => &l.properties,
+            Layer::Objects(l) => &l.properties,
+            Layer::Group(l) => &l.properties,
+        }
+    }
+}
+
+impl TileLayer {
+    /// `None` for cells outside of the layer or past the end of `tiles`
+    pub fn gid(&self, cell: glm::UVec2) -> Option<u32> {
+        if cell.x >= self.dims.x || cell.y >= self.dims.y {
+            return None;
+        }
+        let index = u64::from(cell.y) * u64::from(self.dims.x) + u64::from(cell.x);
+        self.tiles.get(index as usize).copied()
+    }
+
+    /// Non-empty cells merged into one rectangle per horizontal run
+    pub fn rectangles(&self, tile: glm::UVec2) -> Vec<Rectangle> {
+        if self.dims.x == 0 {
+            return vec![];
+        }
+        let tile = glm::to_dvec2(tile);
+        let offset = glm::to_dvec2(self.offset);
+        let mut rects = vec![];
+        for (y, row) in self.tiles.chunks(self.dims.x as usize).enumerate() {
+            let mut start = None;
+            for x in 0..=row.len() {
+                let filled = row.get(x).is_some_and(|&gid| gid & GID_MASK != 0);
+                match (start, filled) {
+                    (None, true) => start = Some(x),
+                    (Some(s), false) => {
+                        rects.push(Rectangle {
+                            top_left: offset + glm::dvec2(s as f64, y as f64) * tile,
+                            dims: glm::dvec2((x - s) as f64, 1.) * tile,
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        rects
+    }
+}
+
+impl ObjectLayer {
+    pub fn rectangles(&self) -> Vec<Rectangle> {
+        let offset = glm::to_dvec2(self.offset);
+        self.objects
+            .iter()
+            .filter_map(Object::rectangle)
+            .map(|r| Rectangle {
+                top_left: r.top_left + offset,
+                ..r
+            })
+            .collect()
+    }
+}
+
+impl Object {
+    pub fn rectangle(&self) -> Option<Rectangle> {
+        match self.shape {
+            ObjectShape::Rectangle => {
+                // tile objects are positioned by their bottom left corner
+                let top_left = match self.gid {
+                    Some(_) => self.position - glm::dvec2(0., self.dims.y),
+                    None => self.position,
+                };
+                Some(Rectangle {
+                    top_left,
+                    dims: self.dims,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T> View<'_, T> {
+    /// How many cells a tileset's tiles may spill over into its neighbours
+    fn overhang(&self) -> glm::IVec2 {
+        let tile = glm::to_ivec2(self.map.tile);
+        self.map
+            .tilesets
+            .iter()
+            .map(|t| {
+                let extra = glm::max(glm::to_ivec2(t.tile) - tile, glm::ivec2(0, 0));
+                (extra + tile - glm::ivec2(1, 1)) / tile
+            })
+            .fold(glm::ivec2(0, 0), glm::max)
+    }
+
+    fn show_layer<R: Renderer>(&self, layer: &TileLayer, renderer: &mut R) -> Result<()>
+    where
+        T: Draw<R>,
+    {
+        let map = self.map;
+        let tile = glm::to_ivec2(map.tile);
+        let origin = layer.offset - self.camera;
+        let viewport = glm::to_ivec2(self.viewport);
+        let overhang = self.overhang();
+        let dims = glm::to_ivec2(layer.dims);
+
+        let start_x = ((-origin.x).div_euclid(tile.x) - overhang.x).max(0);
+        let start_y = (-origin.y).div_euclid(tile.y).max(0);
+        let end_x = ((viewport.x - origin.x).div_euclid(tile.x) + 1).min(dims.x);
+        let end_y = ((viewport.y - origin.y).div_euclid(tile.y) + 1 + overhang.y).min(dims.y);
+
+        for y in start_y..end_y {
+            for x in start_x..end_x {
+                let raw = layer.gid(glm::uvec2(x as u32, y as u32)).ok_or_else(|| {
+                    failure::err_msg(format!("layer '{}' has no tile at {}x{}", layer.name, x, y))
+                })?;
+                let gid = raw & GID_MASK;
+                if gid == 0 {
+                    continue;
+                }
+                let tileset = map.tileset(gid).ok_or_else(|| {
+                    failure::err_msg(format!(
+                        "tile {} in layer '{}' does not belong to a tileset",
+                        gid, layer.name
+                    ))
+                })?;
+                let dst = align::left(origin.x + x * tile.x)
+                    .bottom(origin.y + (y + 1) * tile.y)
+                    .dims(tileset.tile);
+                let tile = tileset.sheet.tile(gid - tileset.first_gid);
+                renderer.draw(&tile, tile_options(raw, tileset.tile).at(dst))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Renderer, T: Draw<R>> Show<R> for View<'_, T> {
+    fn show(&self, renderer: &mut R) -> Result<()> {
+        self.map
+            .layers
+            .iter()
+            .filter_map(|l| match l {
+                Layer::Tiles(l) if l.visible => Some(l),
+                _ => None,
+            })
+            .try_for_each(|l| self.show_layer(l, renderer))
+    }
+}
+
+/// Tiled flips diagonally first and then horizontally and vertically; a
+/// diagonal flip is drawn as a quarter turn of the (re)flipped tile
+fn tile_options(raw: u32, dims: glm::UVec2) -> Options {
+    let horizontal = raw & FLIPPED_HORIZONTALLY != 0;
+    let vertical = raw & FLIPPED_VERTICALLY != 0;
+    let diagonal = raw & FLIPPED_DIAGONALLY != 0;
+
+    let (horizontal, vertical) = if diagonal {
+        (vertical, !horizontal)
+    } else {
+        (horizontal, vertical)
+    };
+
+    let mut options = options::none();
+    options.flip = match (horizontal, vertical) {
+        (true, true) => Some(Flip::Both),
+        (true, false) => Some(Flip::Horizontal),
+        (false, true) => Some(Flip::Vertical),
+        (false, false) => None,
+    };
+    if diagonal {
+        options.rotation = Some(Rotation {
+            angle: 90.,
+            center: glm::to_ivec2(dims) / 2,
+        });
+    }
+    options
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u32>> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(data.trim())?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Parses Tiled's `#AARRGGBB` or `#RRGGBB` colors
+fn parse_color(color: &str) -> Result<ColorRGBA> {
+    let hex = color.trim_start_matches('#');
+    let value = u32::from_str_radix(hex, 16)
+        .map_err(|_| failure::err_msg(format!("invalid color '{}'", color)))?;
+    let [a, r, g, b] = value.to_be_bytes();
+    match hex.len() {
+        8 => Ok(ColorRGBA(r, g, b, a)),
+        6 => Ok(ColorRGBA(r, g, b, 255)),
+        _ => Err(failure::err_msg(format!("invalid color '{}'", color))),
+    }
+}
+
+fn resolve(dir: &Path, path: &str) -> String {
+    dir.join(path).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{renderer::mocks::MockCanvas, texture::mocks::MockTexture};
+
+    fn map(tiles: Vec<u32>) -> Map<MockTexture> {
+        let data = Data {
+            dims: glm::uvec2(4, 3),
+            tile: glm::uvec2(8, 8),
+            layers: vec![
+                Layer::Tiles(TileLayer {
+                    name: "ground".into(),
+                    visible: true,
+                    offset: glm::ivec2(0, 0),
+                    dims: glm::uvec2(4, 3),
+                    tiles,
+                    properties: Properties::new(),
+                }),
+                Layer::Tiles(TileLayer {
+                    name: "hidden".into(),
+                    visible: false,
+                    offset: glm::ivec2(0, 0),
+                    dims: glm::uvec2(4, 3),
+                    tiles: vec![1; 12],
+                    properties: Properties::new(),
+                }),
+            ],
+            tilesets: vec![TilesetData {
+                first_gid: 1,
+                name: "terrain".into(),
+                image: "terrain.png".into(),
+                grid: Grid::new(glm::uvec2(8, 8)),
+                count: 8,
+            }],
+            properties: Properties::new(),
+        };
+        data.into_map_with(|image| {
+            assert_eq!(image, "terrain.png");
+            Ok(MockTexture {
+                dims: glm::uvec2(32, 16),
+            })
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn looks_up_tiles() {
+        let map = map(vec![0; 12]);
+        assert!(map.tile(0).is_none());
+        assert!(map.tile(9).is_none());
+        let tile = map.tile(6 | FLIPPED_HORIZONTALLY).unwrap();
+        assert_eq!(tile.rect(), glm::uvec4(8, 8, 8, 8));
+    }
+
+    #[test]
+    fn looks_up_cells_inside_layers() {
+        let layer = TileLayer {
+            name: "short".into(),
+            visible: true,
+            offset: glm::ivec2(0, 0),
+            dims: glm::uvec2(4, 3),
+            tiles: vec![1, 2, 3, 4, 5],
+            properties: Properties::new(),
+        };
+        assert_eq!(layer.gid(glm::uvec2(0, 1)), Some(5));
+        assert_eq!(layer.gid(glm::uvec2(4, 0)), None);
+        assert_eq!(layer.gid(glm::uvec2(1, 1)), None);
+        assert_eq!(layer.gid(glm::uvec2(0, 3)), None);
+    }
+
+    #[test]
+    fn shows_visible_layers() {
+        let map = map(vec![1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5]);
+        let mut renderer = MockCanvas::new();
+        assert!(renderer
+            .show(&map.view(glm::ivec2(0, 0), glm::uvec2(32, 24)))
+            .is_ok());
+
+        let texture = MockTexture {
+            dims: glm::uvec2(32, 16),
+        };
+        assert_eq!(
+            renderer.draw,
+            vec![
+                (
+                    texture,
+                    options::at(align::left(0).bottom(8).dims(glm::uvec2(8, 8)))
+                        .from(glm::uvec4(0, 0, 8, 8))
+                ),
+                (
+                    texture,
+                    options::at(align::left(8).bottom(8).dims(glm::uvec2(8, 8)))
+                        .from(glm::uvec4(8, 0, 8, 8))
+                ),
+                (
+                    texture,
+                    options::at(align::left(24).bottom(24).dims(glm::uvec2(8, 8)))
+                        .from(glm::uvec4(0, 8, 8, 8))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn culls_to_camera() {
+        let map = map((1..=12).map(|i| (i - 1) % 8 + 1).collect());
+        let mut renderer = MockCanvas::new();
+        let view = map.view(glm::ivec2(12, 4), glm::uvec2(8, 8));
+        assert!(renderer.show(&view).is_ok());
+
+        let dsts: Vec<_> = renderer
+            .draw
+            .iter()
+            .map(|(_, o)| o.dst.unwrap())
+            .map(|d| glm::ivec2(d.left(), d.top()))
+            .collect();
+        assert_eq!(
+            dsts,
+            vec![
+                glm::ivec2(-4, -4),
+                glm::ivec2(4, -4),
+                glm::ivec2(-4, 4),
+                glm::ivec2(4, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_gid_errors() {
+        let map = map(vec![42; 12]);
+        let mut renderer = MockCanvas::new();
+        assert!(renderer
+            .show(&map.view(glm::ivec2(0, 0), glm::uvec2(8, 8)))
+            .is_err());
+    }
+
+    #[test]
+    fn flipped_tiles() {
+        let dims = glm::uvec2(8, 8);
+        assert_eq!(tile_options(3, dims), options::none());
+        assert_eq!(
+            tile_options(3 | FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY, dims),
+            options::flip(Flip::Both)
+        );
+
+        let quarter_turn = Rotation {
+            angle: 90.,
+            center: glm::ivec2(4, 4),
+        };
+        assert_eq!(
+            tile_options(3 | FLIPPED_DIAGONALLY | FLIPPED_HORIZONTALLY, dims),
+            options::rotate(quarter_turn)
+        );
+        assert_eq!(
+            tile_options(3 | FLIPPED_DIAGONALLY, dims),
+            options::rotate(quarter_turn).flip(Flip::Vertical)
+        );
+    }
+
+    #[test]
+    fn tile_layer_rectangles() {
+        let map = map(vec![1, 1, 0, 1, 0, 0, 0, 0, 2, 2, 2, 2]);
+        let rects: Vec<_> = map
+            .rectangles("ground")
+            .into_iter()
+            .map(|r| (r.top_left, r.dims))
+            .collect();
+        assert_eq!(
+            rects,
+            vec![
+                (glm::dvec2(0., 0.), glm::dvec2(16., 8.)),
+                (glm::dvec2(24., 0.), glm::dvec2(8., 8.)),
+                (glm::dvec2(0., 16.), glm::dvec2(32., 8.)),
+            ]
+        );
+        assert!(map.rectangles("missing").is_empty());
+    }
+
+    #[test]
+    fn object_layer_rectangles() {
+        let object = |gid, shape| Object {
+            id: 1,
+            name: String::new(),
+            kind: "wall".into(),
+            position: glm::dvec2(10., 20.),
+            dims: glm::dvec2(5., 6.),
+            gid,
+            shape,
+            visible: true,
+            properties: Properties::new(),
+        };
+        let layer = ObjectLayer {
+            name: "walls".into(),
+            visible: true,
+            offset: glm::ivec2(1, 2),
+            objects: vec![
+                object(None, ObjectShape::Rectangle),
+                object(Some(3), ObjectShape::Rectangle),
+                object(None, ObjectShape::Ellipse),
+            ],
+            properties: Properties::new(),
+        };
+        let rects: Vec<_> = layer
+            .rectangles()
+            .into_iter()
+            .map(|r| (r.top_left, r.dims))
+            .collect();
+        assert_eq!(
+            rects,
+            vec![
+                (glm::dvec2(11., 22.), glm::dvec2(5., 6.)),
+                (glm::dvec2(11., 16.), glm::dvec2(5., 6.)),
+            ]
+        );
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(
+            parse_color("#80ff0010").unwrap(),
+            ColorRGBA(255, 0, 16, 128)
+        );
+        assert_eq!(parse_color("#ff0010").unwrap(), ColorRGBA(255, 0, 16, 255));
+        assert!(parse_color("#ff00").is_err());
+    }
+}
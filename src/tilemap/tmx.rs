@@ -0,0 +1,506 @@
+use super::{
+    decode_base64, json, parse_color, resolve, Data, GroupLayer, Layer, Object, ObjectLayer,
+    ObjectShape, Properties, Property, TileLayer, TilesetData,
+};
+use crate::{animation::Grid, Result};
+
+use roxmltree::{Document, Node};
+
+use std::{fs, path::Path, str::FromStr};
+
+pub(super) fn parse(xml: &str, dir: &Path) -> Result<Data> {
+    let doc = Document::parse(xml)?;
+    let map = doc.root_element();
+    expect_tag(map, "map")?;
+
+    let orientation = map.attribute("orientation").unwrap_or("orthogonal");
+    if orientation != "orthogonal" {
+        return Err(failure::err_msg(format!(
+            "{} maps are not supported",
+            orientation
+        )));
+    }
+    if attr_or(map, "infinite", 0)? != 0 {
+        return Err(failure::err_msg("infinite maps are not supported"));
+    }
+
+    let tilesets = elements(map, "tileset")
+        .map(|t| {
+            let first_gid = attr(t, "firstgid")?;
+            match t.attribute("source") {
+                Some(source) => {
+                    let path = dir.join(source);
+                    let contents = fs::read_to_string(&path)?;
+                    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+                    if source.ends_with(".tsx") {
+                        parse_tileset(&contents, first_gid, dir)
+                    } else {
+                        json::parse_tileset(&contents, first_gid, dir)
+                    }
+                }
+                None => tileset(t, first_gid, dir),
+            }
+        })
+        .collect::<Result<_>>()?;
+
+    let tile = glm::uvec2(attr(map, "tilewidth")?, attr(map, "tileheight")?);
+    if tile.x == 0 || tile.y == 0 {
+        return Err(failure::err_msg(format!(
+            "map tiles must not be empty but are {}x{}",
+            tile.x, tile.y
+        )));
+    }
+
+    let mut layers = vec![];
+    flatten(map, glm::ivec2(0, 0), true, &mut layers)?;
+
+    Ok(Data {
+        dims: glm::uvec2(attr(map, "width")?, attr(map, "height")?),
+        tile,
+        layers,
+        tilesets,
+        properties: properties(map)?,
+    })
+}
+
+pub(super) fn parse_tileset(xml: &str, first_gid: u32, dir: &Path) -> Result<TilesetData> {
+    let doc = Document::parse(xml)?;
+    let node = doc.root_element();
+    expect_tag(node, "tileset")?;
+    tileset(node, first_gid, dir)
+}
+
+fn tileset(node: Node<'_, '_>, first_gid: u32, dir: &Path) -> Result<TilesetData> {
+    let name = node.attribute("name").unwrap_or_default().to_string();
+    let image = elements(node, "image")
+        .next()
+        .and_then(|i| i.attribute("source"))
+        .ok_or_else(|| {
+            failure::err_msg(format!(
+                "tileset '{}' must be a single image with uniform tiles",
+                name
+            ))
+        })?;
+    let tile = glm::uvec2(attr(node, "tilewidth")?, attr(node, "tileheight")?);
+
+    Ok(TilesetData {
+        first_gid,
+        image: resolve(dir, image),
+        grid: Grid::new(tile)
+            .margin(attr_or(node, "margin", 0)?)
+            .spacing(attr_or(node, "spacing", 0)?),
+        count: attr(node, "tilecount")?,
+        name,
+    })
+}
+
+/// Group layers are followed by their children, which inherit the group's
+/// offset and visibility
+fn flatten(
+    parent: Node<'_, '_>,
+    offset: glm::IVec2,
+    visible: bool,
+    layers: &mut Vec<Layer>,
+) -> Result<()> {
+    for node in parent.children().filter(Node::is_element) {
+        let offset = offset
+            + glm::ivec2(
+                attr_or::<f64>(node, "offsetx", 0.)? as i32,
+                attr_or::<f64>(node, "offsety", 0.)? as i32,
+            );
+        let visible = visible && attr_or(node, "visible", 1)? != 0;
+        let name = || node.attribute("name").unwrap_or_default().to_string();
+
+        match node.tag_name().name() {
+            "layer" => {
+                let dims = glm::uvec2(attr(node, "width")?, attr(node, "height")?);
+                if dims.x == 0 || dims.y == 0 {
+                    return Err(failure::err_msg(format!(
+                        "layer '{}' must not be empty",
+                        name()
+                    )));
+                }
+                let tiles = tiles(node)?;
+                let count = u64::from(dims.x) * u64::from(dims.y);
+                if tiles.len() as u64 != count {
+                    return Err(failure::err_msg(format!(
+                        "layer '{}' has {} tiles but should have {}",
+                        name(),
+                        tiles.len(),
+                        count
+                    )));
+                }
+                layers.push(Layer::Tiles(TileLayer {
+                    name: name(),
+                    visible,
+                    offset,
+                    dims,
+                    tiles,
+                    properties: properties(node)?,
+                }));
+            }
+            "objectgroup" => {
+                let objects = elements(node, "object")
+                    .map(object)
+                    .collect::<Result<_>>()?;
+                layers.push(Layer::Objects(ObjectLayer {
+                    name: name(),
+                    visible,
+                    offset,
+                    objects,
+                    properties: properties(node)?,
+                }));
+            }
+            "group" => {
+                let index = layers.len();
+                layers.push(Layer::Group(GroupLayer {
+                    name: name(),
+                    visible,
+                    offset,
+                    layers: 0,
+                    properties: properties(node)?,
+                }));
+                flatten(node, offset, visible, layers)?;
+                let count = layers.len() - index - 1;
+                if let Layer::Group(group) = &mut layers[index] {
+                    group.layers = count;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn tiles(layer: Node<'_, '_>) -> Result<Vec<u32>> {
+    let data = elements(layer, "data")
+        .next()
+        .ok_or_else(|| failure::err_msg("tile layer is missing its data"))?;
+    let text = data.text().unwrap_or_default();
+
+    match (data.attribute("encoding"), data.attribute("compression")) {
+        (_, Some(compression)) => Err(failure::err_msg(format!(
+            "{} compressed layer data is not supported",
+            compression
+        ))),
+        (Some("csv"), None) => text
+            .split(',')
+            .map(|gid| gid.trim().parse().map_err(Into::into))
+            .collect(),
+        (Some("base64"), None) => decode_base64(text),
+        (None, None) => elements(data, "tile")
+            .map(|t| attr_or(t, "gid", 0))
+            .collect(),
+        (Some(encoding), None) => Err(failure::err_msg(format!(
+            "{} layer encoding is not supported",
+            encoding
+        ))),
+    }
+}
+
+fn object(node: Node<'_, '_>) -> Result<Object> {
+    let points = |points: &str| -> Result<Vec<glm::DVec2>> {
+        points
+            .split_whitespace()
+            .map(|p| {
+                let mut coords = p.split(',').map(f64::from_str);
+                match (coords.next(), coords.next()) {
+                    (Some(Ok(x)), Some(Ok(y))) => Ok(glm::dvec2(x, y)),
+                    _ => Err(failure::err_msg(format!("invalid point '{}'", p))),
+                }
+            })
+            .collect()
+    };
+
+    let mut shape = ObjectShape::Rectangle;
+    for child in node.children().filter(Node::is_element) {
+        shape = match child.tag_name().name() {
+            "ellipse" => ObjectShape::Ellipse,
+            "point" => ObjectShape::Point,
+            "polygon" => {
+                ObjectShape::Polygon(points(child.attribute("points").unwrap_or_default())?)
+            }
+            "polyline" => {
+                ObjectShape::Polyline(points(child.attribute("points").unwrap_or_default())?)
+            }
+            _ => continue,
+        };
+    }
+
+    Ok(Object {
+        id: attr(node, "id")?,
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        kind: node
+            .attribute("type")
+            .or_else(|| node.attribute("class"))
+            .unwrap_or_default()
+            .to_string(),
+        position: glm::dvec2(attr(node, "x")?, attr(node, "y")?),
+        dims: glm::dvec2(attr_or(node, "width", 0.)?, attr_or(node, "height", 0.)?),
+        gid: node.attribute("gid").map(u32::from_str).transpose()?,
+        shape,
+        visible: attr_or(node, "visible", 1)? != 0,
+        properties: properties(node)?,
+    })
+}
+
+fn properties(node: Node<'_, '_>) -> Result<Properties> {
+    elements(node, "properties")
+        .flat_map(|p| elements(p, "property"))
+        .map(|p| {
+            let name = attr::<String>(p, "name")?;
+            let value = p
+                .attribute("value")
+                .or_else(|| p.text())
+                .unwrap_or_default();
+            let value = match p.attribute("type").unwrap_or("string") {
+                "bool" => Property::Bool(value.parse()?),
+                "int" => Property::Int(value.parse()?),
+                "float" => Property::Float(value.parse()?),
+                "object" => Property::Object(value.parse()?),
+                "color" => Property::Color(parse_color(value)?),
+                "file" => Property::File(value.to_string()),
+                "string" => Property::String(value.to_string()),
+                kind => {
+                    return Err(failure::err_msg(format!(
+                        "property '{}' has unsupported type {}",
+                        name, kind
+                    )))
+                }
+            };
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn elements<'a, 'input: 'a>(
+    node: Node<'a, 'input>,
+    tag: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+    node.children().filter(move |n| n.has_tag_name(tag))
+}
+
+fn expect_tag(node: Node<'_, '_>, tag: &str) -> Result<()> {
+    if node.has_tag_name(tag) {
+        Ok(())
+    } else {
+        Err(failure::err_msg(format!(
+            "expected <{}> but found <{}>",
+            tag,
+            node.tag_name().name()
+        )))
+    }
+}
+
+fn attr<T: FromStr>(node: Node<'_, '_>, name: &str) -> Result<T> {
+    let value = node.attribute(name).ok_or_else(|| {
+        failure::err_msg(format!(
+            "<{}> is missing the '{}' attribute",
+            node.tag_name().name(),
+            name
+        ))
+    })?;
+    parse_attr(node, name, value)
+}
+
+fn attr_or<T: FromStr>(node: Node<'_, '_>, name: &str, default: T) -> Result<T> {
+    node.attribute(name)
+        .map_or(Ok(default), |v| parse_attr(node, name, v))
+}
+
+fn parse_attr<T: FromStr>(node: Node<'_, '_>, name: &str, value: &str) -> Result<T> {
+    value.parse().map_err(|_| {
+        failure::err_msg(format!(
+            "<{}> has an invalid '{}' attribute: {}",
+            node.tag_name().name(),
+            name,
+            value
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="2" height="2"
+     tilewidth="16" tileheight="16" infinite="0">
+  <properties>
+    <property name="title" value="Caves"/>
+    <property name="enemies" type="int" value="3"/>
+    <property name="intro">Welcome
+to the caves</property>
+  </properties>
+  <tileset firstgid="1" name="cave" tilewidth="16" tileheight="16" spacing="1" margin="1"
+           tilecount="6" columns="3">
+    <image source="cave.png" width="52" height="35"/>
+  </tileset>
+  <layer id="1" name="csv" width="2" height="2">
+    <data encoding="csv">
+1,2,
+0,3221225476
+</data>
+  </layer>
+  <group id="2" name="details" offsetx="3" offsety="4">
+    <properties><property name="parallax" type="float" value="0.5"/></properties>
+    <layer id="3" name="base64" width="2" height="2" visible="0" offsetx="1">
+      <data encoding="base64">
+        AwAAAAAAAAABAABAAAAAAA==
+      </data>
+    </layer>
+    <layer id="4" name="xml" width="2" height="2">
+      <data>
+        <tile gid="5"/>
+        <tile/>
+        <tile/>
+        <tile gid="6"/>
+      </data>
+    </layer>
+  </group>
+  <objectgroup id="5" name="collision">
+    <object id="1" name="floor" type="solid" x="0" y="24" width="32" height="8"/>
+    <object id="2" x="4" y="4"><point/></object>
+    <object id="3" class="slope" x="0" y="0">
+      <polygon points="0,0 16,0 16,-16"/>
+      <properties><property name="steep" type="bool" value="true"/></properties>
+    </object>
+    <object id="4" gid="2" x="8" y="16" width="16" height="16"/>
+  </objectgroup>
+</map>
+"#;
+
+    #[test]
+    fn parses_map() {
+        let data = parse(MAP, Path::new("levels")).unwrap();
+        assert_eq!(data.dims, glm::uvec2(2, 2));
+        assert_eq!(data.tile, glm::uvec2(16, 16));
+        assert_eq!(data.properties["title"], Property::String("Caves".into()));
+        assert_eq!(data.properties["enemies"], Property::Int(3));
+        assert_eq!(
+            data.properties["intro"],
+            Property::String("Welcome\nto the caves".into())
+        );
+        assert_eq!(
+            data.tilesets,
+            vec![TilesetData {
+                first_gid: 1,
+                name: "cave".into(),
+                image: Path::new("levels/cave.png").to_string_lossy().into_owned(),
+                grid: Grid::new(glm::uvec2(16, 16)).margin(1).spacing(1),
+                count: 6,
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_tile_layers() {
+        let data = parse(MAP, Path::new("")).unwrap();
+        let tiles: Vec<_> = data
+            .layers
+            .iter()
+            .filter_map(|l| match l {
+                Layer::Tiles(l) => Some((l.name.as_str(), l.visible, l.offset, l.tiles.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tiles,
+            vec![
+                ("csv", true, glm::ivec2(0, 0), vec![1, 2, 0, 0xC000_0004]),
+                (
+                    "base64",
+                    false,
+                    glm::ivec2(4, 4),
+                    vec![3, 0, 0x4000_0001, 0]
+                ),
+                ("xml", true, glm::ivec2(3, 4), vec![5, 0, 0, 6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_objects() {
+        let data = parse(MAP, Path::new("")).unwrap();
+        let layer = match &data.layers[4] {
+            Layer::Objects(layer) => layer,
+            l => panic!("expected an object layer; got {:?}", l),
+        };
+        let objects = &layer.objects;
+        assert_eq!(objects.len(), 4);
+        assert_eq!(objects[0].kind, "solid");
+        assert_eq!(objects[0].dims, glm::dvec2(32., 8.));
+        assert_eq!(objects[1].shape, ObjectShape::Point);
+        assert_eq!(objects[2].kind, "slope");
+        assert_eq!(
+            objects[2].shape,
+            ObjectShape::Polygon(vec![
+                glm::dvec2(0., 0.),
+                glm::dvec2(16., 0.),
+                glm::dvec2(16., -16.)
+            ])
+        );
+        assert_eq!(objects[2].properties["steep"], Property::Bool(true));
+        assert_eq!(objects[3].gid, Some(2));
+
+        let rects: Vec<_> = layer
+            .rectangles()
+            .into_iter()
+            .map(|r| (r.top_left, r.dims))
+            .collect();
+        assert_eq!(
+            rects,
+            vec![
+                (glm::dvec2(0., 24.), glm::dvec2(32., 8.)),
+                (glm::dvec2(8., 0.), glm::dvec2(16., 16.)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_groups() {
+        let data = parse(MAP, Path::new("")).unwrap();
+        match &data.layers[1] {
+            Layer::Group(group) => {
+                assert_eq!(group.name, "details");
+                assert_eq!(group.offset, glm::ivec2(3, 4));
+                assert_eq!(group.layers, 2);
+                assert_eq!(group.properties["parallax"], Property::Float(0.5));
+            }
+            l => panic!("expected a group layer; got {:?}", l),
+        }
+    }
+
+    #[test]
+    fn rejects_empty_tiles_and_layers() {
+        let xml = MAP.replace(
+            r#"tilewidth="16" tileheight="16" infinite"#,
+            r#"tilewidth="0" tileheight="16" infinite"#,
+        );
+        assert!(parse(&xml, Path::new("")).is_err());
+
+        let xml = MAP.replace(r#"name="xml" width="2""#, r#"name="xml" width="0""#);
+        assert!(parse(&xml, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn rejects_compressed_data() {
+        let xml = MAP.replace(
+            r#"<data encoding="base64">"#,
+            r#"<data encoding="base64" compression="zlib">"#,
+        );
+        assert!(parse(&xml, Path::new("")).is_err());
+    }
+
+    #[test]
+    fn rejects_mismatched_layers() {
+        let xml = MAP.replace("0,3221225476", "0");
+        assert!(parse(&xml, Path::new("")).is_err());
+
+        let xml = MAP.replace(
+            r#"name="csv" width="2" height="2""#,
+            r#"name="csv" width="65536" height="65536""#,
+        );
+        assert!(parse(&xml, Path::new("")).is_err());
+    }
+}
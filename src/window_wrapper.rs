@@ -1,3 +1,134 @@
+use crate::{
+    renderer::{Destination, Draw, Options, Renderer},
+    shape::{Intersect, Shape},
+    Result,
+};
+
+/// Window whose edges wrap around to the opposite side, so that whatever
+/// leaves through one edge comes back in through the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wrapper {
+    size: glm::UVec2,
+}
+
+impl Wrapper {
+    pub fn new(size: glm::UVec2) -> Result<Self> {
+        if size.x == 0 || size.y == 0 {
+            return Err(failure::err_msg("wrapped windows must not be empty"));
+        }
+        Ok(Wrapper { size })
+    }
+
+    pub fn size(&self) -> glm::UVec2 {
+        self.size
+    }
+
+    /// `point` moved by whole windows to be inside of the window
+    pub fn wrap_point(&self, point: glm::DVec2) -> glm::DVec2 {
+        let size = glm::to_dvec2(self.size);
+        glm::dvec2(point.x.rem_euclid(size.x), point.y.rem_euclid(size.y))
+    }
+
+    /// `shape` moved by whole windows so that its center is in the window
+    pub fn wrap<S: Shape>(&self, shape: S) -> S {
+        let center = self.wrap_point(shape.center());
+        shape.center_at(center)
+    }
+
+    /// Shortest way from `from` to `to`, going across edges if that is
+    /// shorter
+    pub fn offset(&self, from: glm::DVec2, to: glm::DVec2) -> glm::DVec2 {
+        let size = glm::to_dvec2(self.size);
+        let wrap = |d: f64, size: f64| d - size * (d / size).round();
+        let d = to - from;
+        glm::dvec2(wrap(d.x, size.x), wrap(d.y, size.y))
+    }
+
+    /// Like `Shape::distance` but going across edges if that is shorter
+    pub fn distance(&self, a: &impl Shape, b: &impl Shape) -> f64 {
+        glm::length(self.offset(a.center(), b.center()))
+    }
+
+    /// `dst` inside of the window and its copies past every edge it crosses
+    pub fn destinations(&self, dst: Destination) -> Vec<Destination> {
+        let rect = glm::ivec4(dst.left(), dst.top(), dst.dims.x as i32, dst.dims.y as i32);
+        wrap_rects(rect, self.size)
+            .iter()
+            .flatten()
+            .map(|&copy| Destination::from(copy))
+            .collect()
+    }
+
+    /// Draws `asset` at every one of the `destinations` of `options.dst`;
+    /// without a destination it is drawn once as is
+    pub fn draw<R: Renderer>(
+        &self,
+        asset: &impl Draw<R>,
+        options: Options,
+        renderer: &mut R,
+    ) -> Result<()> {
+        let dst = match options.dst {
+            Some(dst) => dst,
+            None => return renderer.draw(asset, options),
+        };
+        let copies: Vec<_> = self
+            .destinations(dst)
+            .into_iter()
+            .map(|copy| {
+                let shift = glm::ivec2(copy.left() - dst.left(), copy.top() - dst.top());
+                match options.precise_dst() {
+                    Some(fdst) => options.clone().at_precise(fdst.nudge(glm::to_dvec2(shift))),
+                    None => options.clone().at(copy),
+                }
+            })
+            .collect();
+        asset.draw_all(&copies, renderer)
+    }
+
+    /// `shape` moved by whole windows to every place where part of it shows
+    pub fn copies<S: Shape + Clone>(&self, shape: &S) -> Vec<S> {
+        let aabb = shape.aabb();
+        let top_left = glm::floor(aabb.top_left);
+        let dims = glm::ceil(aabb.top_left + aabb.dims) - top_left;
+        let rect = glm::ivec4(
+            top_left.x as i32,
+            top_left.y as i32,
+            dims.x as i32,
+            dims.y as i32,
+        );
+        wrap_rects(rect, self.size)
+            .iter()
+            .flatten()
+            .map(|copy| {
+                let shift = glm::dvec2(f64::from(copy.x - rect.x), f64::from(copy.y - rect.y));
+                shape.clone().nudge(shift)
+            })
+            .collect()
+    }
+
+    pub fn intersects<S, F>(&self, object: &S, fixed: &F) -> bool
+    where
+        S: Shape + Clone + Intersect<F>,
+        F: Shape + Clone,
+    {
+        self.mtv(object, fixed).is_some()
+    }
+
+    /// Like `Intersect::mtv` but across edges; shapes that overlap on more
+    /// than one side move out of the deepest overlap
+    pub fn mtv<S, F>(&self, object: &S, fixed: &F) -> Option<glm::DVec2>
+    where
+        S: Shape + Clone + Intersect<F>,
+        F: Shape + Clone,
+    {
+        let fixed = self.copies(fixed);
+        self.copies(object)
+            .iter()
+            .flat_map(|object| fixed.iter().filter_map(move |fixed| object.mtv(fixed)))
+            .max_by(|x, y| glm::length(*x).total_cmp(&glm::length(*y)))
+    }
+}
+
 pub fn wrap_rects(original: glm::IVec4, max: glm::UVec2) -> [Option<glm::IVec4>; 4] {
     let max = glm::to_ivec2(max);
     let left = (original.x + max.x) % max.x;
@@ -54,6 +185,11 @@ fn vert_wrap(left: i32, top: i32, original: glm::IVec4, max: glm::IVec2) -> Opti
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{
+        renderer::{mocks::MockCanvas, options, FDestination},
+        shape::{Circle, Rectangle},
+        texture::mocks::MockTexture,
+    };
 
     #[test]
     fn no_wrapping() {
@@ -133,4 +269,127 @@ mod test {
         assert_eq!(glm::ivec4(-2, 27, 10, 10), rects[1]);
         assert_eq!(glm::ivec4(38, 27, 10, 10), rects[0]);
     }
+
+    fn wrapper() -> Wrapper {
+        Wrapper::new(glm::uvec2(40, 30)).unwrap()
+    }
+
+    #[test]
+    fn rejects_empty_windows() {
+        assert!(Wrapper::new(glm::uvec2(0, 30)).is_err());
+        assert!(Wrapper::new(glm::uvec2(40, 0)).is_err());
+    }
+
+    #[test]
+    fn wraps_points_and_shapes() {
+        let wrapper = wrapper();
+        assert_eq!(
+            wrapper.wrap_point(glm::dvec2(-2., 65.)),
+            glm::dvec2(38., 5.)
+        );
+        let circle = Circle {
+            center: glm::dvec2(41., -1.),
+            radius: 2.,
+        };
+        assert_eq!(wrapper.wrap(circle).center, glm::dvec2(1., 29.));
+    }
+
+    #[test]
+    fn distance_across_edges() {
+        let wrapper = wrapper();
+        let a = Circle {
+            center: glm::dvec2(1., 2.),
+            radius: 1.,
+        };
+        let b = Circle {
+            center: glm::dvec2(37., 26.),
+            radius: 1.,
+        };
+        assert_eq!(wrapper.offset(a.center, b.center), glm::dvec2(-4., -6.));
+        assert_eq!(wrapper.distance(&a, &b), 52_f64.sqrt());
+        assert_eq!(wrapper.distance(&a, &a.nudge(glm::dvec2(3., 4.))), 5.);
+    }
+
+    #[test]
+    fn draws_wrapped_copies() {
+        let mut canvas = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        let options = options::at_precise(FDestination::new(
+            glm::dvec2(-2.25, 3.),
+            glm::dvec2(10., 10.),
+        ));
+        assert!(wrapper().draw(&texture, options, &mut canvas).is_ok());
+
+        let drawn: Vec<_> = canvas.draw.iter().map(|(_, o)| o.dst.unwrap()).collect();
+        assert_eq!(
+            drawn,
+            vec![
+                Destination::from(glm::ivec4(38, 3, 10, 10)),
+                Destination::from(glm::ivec4(-2, 3, 10, 10)),
+            ]
+        );
+        let fdst = canvas.draw[0].1.precise_dst().unwrap();
+        assert_eq!(fdst.top_left, glm::dvec2(37.75, 3.));
+    }
+
+    #[test]
+    fn draws_moved_destinations() {
+        let mut canvas = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        let mut options = options::at_precise(FDestination::new(
+            glm::dvec2(-2.25, 3.),
+            glm::dvec2(10., 10.),
+        ));
+        let moved = Destination::from(glm::ivec4(5, 3, 10, 10));
+        options.dst = Some(moved);
+        assert!(wrapper().draw(&texture, options, &mut canvas).is_ok());
+
+        assert_eq!(canvas.draw.len(), 1);
+        assert_eq!(canvas.draw[0].1.dst, Some(moved));
+        assert_eq!(canvas.draw[0].1.precise_dst(), None);
+    }
+
+    #[test]
+    fn draws_without_destination_once() {
+        let mut canvas = MockCanvas::new();
+        let texture = MockTexture {
+            dims: glm::uvec2(10, 10),
+        };
+        assert!(wrapper()
+            .draw(&texture, options::none(), &mut canvas)
+            .is_ok());
+        assert_eq!(canvas.draw.len(), 1);
+    }
+
+    #[test]
+    fn intersects_across_edges() {
+        let wrapper = wrapper();
+        let ship = Circle {
+            center: glm::dvec2(1., 15.),
+            radius: 2.,
+        };
+        let rock = Rectangle {
+            top_left: glm::dvec2(36., 10.),
+            dims: glm::dvec2(4.5, 10.),
+        };
+        assert!(!ship.intersects(&rock));
+        assert!(wrapper.intersects(&ship, &rock));
+        assert_eq!(wrapper.mtv(&ship, &rock), Some(glm::dvec2(1.5, 0.)));
+        assert_eq!(wrapper.mtv(&rock, &ship), Some(glm::dvec2(-1.5, 0.)));
+
+        let corner = Circle {
+            center: glm::dvec2(-39.5, 29.5),
+            radius: 1.,
+        };
+        let far_corner = Circle {
+            center: glm::dvec2(39.5, 0.5),
+            radius: 1.,
+        };
+        assert!(wrapper.intersects(&corner, &far_corner));
+        assert!(!wrapper.intersects(&ship, &far_corner));
+    }
 }